@@ -4,11 +4,15 @@
 //      Proprietary and confidential                                               /
 ////////////////////////////////////////////////////////////////////////////////////
 
-use gl_generator::{Api, DebugPrints, Fallbacks, Profile, Registry};
-use std::io::{BufReader, Read};
+use gl_generator::{read_allowlist, used_idents, Api, CallStats, DebugPrints, GeneratorOptions, Profile, RegistryBuilder};
 use std::{env, fs::File, path::Path};
-fn main() {}
-fn a() {
+
+fn main() {
+    // the parsed registry is cached in OUT_DIR, or in GL_GENERATOR_CACHE_DIR if set
+    println!("cargo:rerun-if-env-changed=GL_GENERATOR_CACHE_DIR");
+    // only generates the commands of an allowlist file, or those used by a directory of sources
+    println!("cargo:rerun-if-env-changed=GL4_6_CORE_ONLY");
+
     let out_dir = env::var("OUT_DIR").unwrap();
     let bindingsdest = Path::new(&out_dir).join("bindings.rs");
 
@@ -19,44 +23,47 @@ fn a() {
     #[cfg(not(feature = "fn_calls_print"))]
     let print = DebugPrints::None;
 
-    Registry::new(
-        Api::Gl,
-        (4, 6),
-        Profile::Core,
-        Fallbacks::All,
-        [
+    #[cfg(feature = "call_stats")]
+    let call_stats = CallStats::Timing;
+    #[cfg(not(feature = "call_stats"))]
+    let call_stats = CallStats::None;
+
+    let mut builder = RegistryBuilder::new(Api::Gl, (4, 6))
+        .profile(Profile::Core)
+        .extensions(&[
             "GL_EXT_texture_filter_anisotropic",
             "GL_ARB_draw_buffers_blend",
             "GL_ARB_program_interface_query",
-        ],
-        print,
-    )
-    .write_bindings(gl_generator::GlobalTypedGenerator, &mut file)
-    .unwrap();
-
-    let cargodir = env::var("CARGO_MANIFEST_DIR").unwrap();
-
-    let libdest = Path::new(&cargodir).join("src").join("lib.rs");
-
-    // check if new bindings are different from previous, if so, copy them into lib.rs
-    if match std::fs::File::open(&libdest) {
-        Ok(file) => {
-            let mut content1 = Vec::new();
-            BufReader::new(file).read_to_end(&mut content1).unwrap();
-            let mut content2 = Vec::new();
-            BufReader::new(File::open(&bindingsdest).expect("Could not open bindings file"))
-                .read_to_end(&mut content2)
-                .unwrap();
-            content1
-                .iter()
-                .zip(content2.iter())
-                .any(|(b1, b2)| b1 != b2)
+        ])
+        .options(GeneratorOptions {
+            debug_prints: print,
+            object_handles: true,
+            call_stats,
+            command_buffer: cfg!(feature = "command_buffer"),
+            shadow_state: cfg!(feature = "shadow_state"),
+            track_objects: cfg!(feature = "track_objects"),
+            ..Default::default()
+        });
+
+    if let Some(only) = env::var_os("GL4_6_CORE_ONLY") {
+        let only = Path::new(&only);
+        println!("cargo:rerun-if-changed={}", only.display());
+        let idents = match only.is_dir() {
+            true => used_idents(only, "gl"),
+            false => read_allowlist(only),
+        };
+        let mut idents = idents.unwrap_or_else(|err| panic!("Could not read {}: {}", only.display(), err));
+        // the commands and enums queried by `gl::state`
+        if cfg!(feature = "state") {
+            println!("cargo:rerun-if-changed=src/state.rs");
+            idents.extend(used_idents("src/state.rs", "gl").expect("Could not read src/state.rs"));
         }
-        _ => true,
-    } {
-        let _ = std::fs::remove_file(&libdest);
-        std::fs::create_dir_all(&libdest.parent().unwrap())
-            .expect("Failed to create dirs for lib.rs");
-        std::fs::copy(bindingsdest, libdest).expect("Could not copy bindings into lib.rs");
+        builder = builder.only(idents);
     }
+
+    builder
+        .build()
+        .unwrap_or_else(|err| panic!("{}", err))
+        .write_bindings(gl_generator::GlobalTypedGenerator, &mut file)
+        .unwrap();
 }
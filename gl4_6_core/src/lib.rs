@@ -0,0 +1,14 @@
+//! OpenGL 4.6 core bindings, generated into `OUT_DIR` by the build script.
+//!
+//! If `GL4_6_CORE_ONLY` is set to an allowlist file, with one command or enum per line, or to a
+//!  directory of sources using the bindings as `gl::`, only those commands are generated.
+//!  The commands and enums queried by `state::snapshot()` are kept with the `state` feature.
+
+#![allow(clippy::all)]
+
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+#[cfg(feature = "loader")]
+pub mod loader;
+#[cfg(feature = "state")]
+pub mod state;
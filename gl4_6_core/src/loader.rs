@@ -0,0 +1,142 @@
+//! A loader opening the system GL libraries itself, so that command-line tools and tests can load
+//!  the commands without a windowing crate.
+//!
+//! ```no_run
+//! let _loader = gl::loader::load().unwrap_or_else(|err| panic!("{}", err));
+//! // make a context current, e.g. with EGL, before calling any commands
+//! ```
+
+use libloading::Library;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_void};
+use std::{error, fmt, ptr};
+
+type GetProcAddress = unsafe extern "system" fn(*const c_char) -> *const c_void;
+
+/// The sets of libraries tried in order. The last library of a set exports the function resolving
+///  the commands which are not exported by any of the libraries.
+const CANDIDATES: &[(&[&str], &str)] = &[
+    // GLX, the legacy library exports everything
+    (&["libGL.so.1"], "glXGetProcAddressARB"),
+    // GLVND, the commands are exported by libOpenGL and resolved by EGL
+    (&["libOpenGL.so.0", "libEGL.so.1"], "eglGetProcAddress"),
+    // EGL only
+    (&["libEGL.so.1"], "eglGetProcAddress"),
+];
+
+/// The opened GL libraries, which are closed on drop.
+///
+/// The loaded commands must not be called after the loader was dropped.
+pub struct Loader {
+    libs: Vec<Library>,
+    get_proc_address: GetProcAddress,
+}
+
+impl Loader {
+    /// Opens the first available set of GL libraries.
+    pub fn new() -> Result<Loader, LoadError> {
+        let mut errors = Vec::new();
+        for &(names, get_proc_address) in CANDIDATES {
+            match Loader::open(names, get_proc_address) {
+                Ok(loader) => return Ok(loader),
+                Err(err) => errors.push(err),
+            }
+        }
+        Err(LoadError { errors })
+    }
+
+    fn open(names: &[&str], get_proc_address: &str) -> Result<Loader, (String, libloading::Error)> {
+        let mut libs = Vec::with_capacity(names.len());
+        for &name in names {
+            // the GL libraries have no initialisers with preconditions
+            libs.push(unsafe { Library::new(name) }.map_err(|err| (name.to_string(), err))?);
+        }
+
+        let lib = libs.last().unwrap();
+        let get_proc_address = unsafe { lib.get::<GetProcAddress>(get_proc_address.as_bytes()) }
+            .map(|symbol| *symbol)
+            .map_err(|err| (names[names.len() - 1].to_string(), err))?;
+        Ok(Loader { libs, get_proc_address })
+    }
+
+    /// Returns the address of the command `symbol`, or null if it is not found.
+    ///
+    /// Exported symbols are preferred, as `eglGetProcAddress` may not resolve core commands.
+    pub fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        for lib in &self.libs {
+            if let Ok(ptr) = unsafe { lib.get::<*const c_void>(symbol.as_bytes()) } {
+                return *ptr;
+            }
+        }
+
+        match CString::new(symbol) {
+            Ok(symbol) => unsafe { (self.get_proc_address)(symbol.as_ptr()) },
+            Err(_) => ptr::null(),
+        }
+    }
+}
+
+/// Opens the system GL libraries and loads the commands with them.
+///
+/// The returned `Loader` has to be kept alive for as long as the commands are called.
+pub fn load() -> Result<Loader, LoadError> {
+    let loader = Loader::new()?;
+    super::load_with(|symbol| loader.get_proc_address(symbol));
+    Ok(loader)
+}
+
+/// None of the sets of GL libraries could be opened.
+#[derive(Debug)]
+pub struct LoadError {
+    /// The library which failed to open or lacked its `GetProcAddress` for each set.
+    pub errors: Vec<(String, libloading::Error)>,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "Could not open the GL libraries")?;
+        for (name, err) in &self.errors {
+            write!(fmt, "\n  {}: {}", name, err)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for LoadError {}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// A loader of libc, with `getenv` standing in for `GetProcAddress` as it takes a C string too.
+    fn libc_loader() -> Loader {
+        Loader::open(&["libc.so.6"], "getenv").unwrap()
+    }
+
+    #[test]
+    fn test_exported_symbols() {
+        let loader = libc_loader();
+        assert!(!loader.get_proc_address("malloc").is_null());
+    }
+
+    #[test]
+    fn test_get_proc_address_fallback() {
+        let loader = libc_loader();
+        assert!(loader.get_proc_address("GL_LOADER_TEST_UNSET").is_null());
+        std::env::set_var("GL_LOADER_TEST_SET", "1");
+        assert!(!loader.get_proc_address("GL_LOADER_TEST_SET").is_null());
+        // not passed on to `GetProcAddress`
+        assert!(loader.get_proc_address("GL_LOADER_TEST_SET\0").is_null());
+    }
+
+    #[test]
+    fn test_open_errors() {
+        let err = Loader::open(&["libc.so.6", "libdoes_not_exist.so"], "getenv").err().unwrap();
+        assert_eq!(err.0, "libdoes_not_exist.so");
+        let err = Loader::open(&["libc.so.6"], "eglGetProcAddress").err().unwrap();
+        assert_eq!(err.0, "libc.so.6");
+
+        let err = LoadError { errors: vec![err] };
+        assert!(err.to_string().starts_with("Could not open the GL libraries\n  libc.so.6: "));
+    }
+}
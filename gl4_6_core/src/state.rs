@@ -0,0 +1,542 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+//! Queries of the current state relevant for drawing, to find out why a draw renders nothing.
+//!
+//! ```no_run
+//! let before = gl::state::snapshot();
+//! // draw
+//! println!("{:#?}", gl::state::snapshot());
+//! for change in before.diff(&gl::state::snapshot()) {
+//!     println!("{}", change);
+//! }
+//! ```
+
+use crate as gl;
+use crate::types;
+use std::{fmt, ptr};
+
+/// A `GLenum` printed by the name of its constant, if it is one of the values of the snapshot.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Enum(pub types::GLenum);
+
+macro_rules! names {
+    ($(gl::$name:ident),* $(,)*) => {
+        &[$((gl::$name, stringify!($name))),*]
+    };
+}
+
+/// The names of the enums in a `Snapshot`. `ZERO` and `ONE` come first as they share their values.
+const NAMES: &[(types::GLenum, &str)] = names![
+    gl::ZERO,
+    gl::ONE,
+    // buffer targets
+    gl::ARRAY_BUFFER,
+    gl::ATOMIC_COUNTER_BUFFER,
+    gl::COPY_READ_BUFFER,
+    gl::COPY_WRITE_BUFFER,
+    gl::DISPATCH_INDIRECT_BUFFER,
+    gl::DRAW_INDIRECT_BUFFER,
+    gl::PIXEL_PACK_BUFFER,
+    gl::PIXEL_UNPACK_BUFFER,
+    gl::QUERY_BUFFER,
+    gl::SHADER_STORAGE_BUFFER,
+    gl::TEXTURE_BUFFER,
+    gl::TRANSFORM_FEEDBACK_BUFFER,
+    gl::UNIFORM_BUFFER,
+    // texture targets
+    gl::TEXTURE_1D,
+    gl::TEXTURE_1D_ARRAY,
+    gl::TEXTURE_2D,
+    gl::TEXTURE_2D_ARRAY,
+    gl::TEXTURE_2D_MULTISAMPLE,
+    gl::TEXTURE_2D_MULTISAMPLE_ARRAY,
+    gl::TEXTURE_3D,
+    gl::TEXTURE_CUBE_MAP,
+    gl::TEXTURE_CUBE_MAP_ARRAY,
+    gl::TEXTURE_RECTANGLE,
+    // blend factors and equations
+    gl::SRC_COLOR,
+    gl::ONE_MINUS_SRC_COLOR,
+    gl::DST_COLOR,
+    gl::ONE_MINUS_DST_COLOR,
+    gl::SRC_ALPHA,
+    gl::ONE_MINUS_SRC_ALPHA,
+    gl::DST_ALPHA,
+    gl::ONE_MINUS_DST_ALPHA,
+    gl::CONSTANT_COLOR,
+    gl::ONE_MINUS_CONSTANT_COLOR,
+    gl::CONSTANT_ALPHA,
+    gl::ONE_MINUS_CONSTANT_ALPHA,
+    gl::SRC_ALPHA_SATURATE,
+    gl::SRC1_COLOR,
+    gl::ONE_MINUS_SRC1_COLOR,
+    gl::SRC1_ALPHA,
+    gl::ONE_MINUS_SRC1_ALPHA,
+    gl::FUNC_ADD,
+    gl::FUNC_SUBTRACT,
+    gl::FUNC_REVERSE_SUBTRACT,
+    gl::MIN,
+    gl::MAX,
+    // depth and stencil functions and operations
+    gl::NEVER,
+    gl::LESS,
+    gl::EQUAL,
+    gl::LEQUAL,
+    gl::GREATER,
+    gl::NOTEQUAL,
+    gl::GEQUAL,
+    gl::ALWAYS,
+    gl::KEEP,
+    gl::REPLACE,
+    gl::INCR,
+    gl::INCR_WRAP,
+    gl::DECR,
+    gl::DECR_WRAP,
+    gl::INVERT,
+    // faces and polygon modes
+    gl::FRONT,
+    gl::BACK,
+    gl::FRONT_AND_BACK,
+    gl::CW,
+    gl::CCW,
+    gl::POINT,
+    gl::LINE,
+    gl::FILL,
+    // vertex attribute types
+    gl::BYTE,
+    gl::UNSIGNED_BYTE,
+    gl::SHORT,
+    gl::UNSIGNED_SHORT,
+    gl::INT,
+    gl::UNSIGNED_INT,
+    gl::HALF_FLOAT,
+    gl::FLOAT,
+    gl::DOUBLE,
+    gl::FIXED,
+    gl::INT_2_10_10_10_REV,
+    gl::UNSIGNED_INT_2_10_10_10_REV,
+    gl::UNSIGNED_INT_10F_11F_11F_REV,
+];
+
+impl fmt::Debug for Enum {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match NAMES.iter().find(|&&(value, _)| value == self.0) {
+            Some((_, name)) => write!(fmt, "{}", name),
+            None => write!(fmt, "{:#06X}", self.0),
+        }
+    }
+}
+
+/// The buffer targets other than `ELEMENT_ARRAY_BUFFER`, which is part of the vertex array, with
+///  the names of their bindings and the versions introducing them.
+const BUFFER_TARGETS: &[(types::GLenum, types::GLenum, (i32, i32))] = &[
+    (gl::ARRAY_BUFFER, gl::ARRAY_BUFFER_BINDING, (1, 5)),
+    (gl::ATOMIC_COUNTER_BUFFER, gl::ATOMIC_COUNTER_BUFFER_BINDING, (4, 2)),
+    (gl::COPY_READ_BUFFER, gl::COPY_READ_BUFFER_BINDING, (3, 1)),
+    (gl::COPY_WRITE_BUFFER, gl::COPY_WRITE_BUFFER_BINDING, (3, 1)),
+    (gl::DISPATCH_INDIRECT_BUFFER, gl::DISPATCH_INDIRECT_BUFFER_BINDING, (4, 3)),
+    (gl::DRAW_INDIRECT_BUFFER, gl::DRAW_INDIRECT_BUFFER_BINDING, (4, 0)),
+    (gl::PIXEL_PACK_BUFFER, gl::PIXEL_PACK_BUFFER_BINDING, (2, 1)),
+    (gl::PIXEL_UNPACK_BUFFER, gl::PIXEL_UNPACK_BUFFER_BINDING, (2, 1)),
+    (gl::QUERY_BUFFER, gl::QUERY_BUFFER_BINDING, (4, 4)),
+    (gl::SHADER_STORAGE_BUFFER, gl::SHADER_STORAGE_BUFFER_BINDING, (4, 3)),
+    (gl::TEXTURE_BUFFER, gl::TEXTURE_BUFFER_BINDING, (3, 1)),
+    (gl::TRANSFORM_FEEDBACK_BUFFER, gl::TRANSFORM_FEEDBACK_BUFFER_BINDING, (3, 0)),
+    (gl::UNIFORM_BUFFER, gl::UNIFORM_BUFFER_BINDING, (3, 1)),
+];
+
+/// The texture targets with the names of their bindings and the versions introducing them.
+const TEXTURE_TARGETS: &[(types::GLenum, types::GLenum, (i32, i32))] = &[
+    (gl::TEXTURE_1D, gl::TEXTURE_BINDING_1D, (1, 0)),
+    (gl::TEXTURE_1D_ARRAY, gl::TEXTURE_BINDING_1D_ARRAY, (3, 0)),
+    (gl::TEXTURE_2D, gl::TEXTURE_BINDING_2D, (1, 0)),
+    (gl::TEXTURE_2D_ARRAY, gl::TEXTURE_BINDING_2D_ARRAY, (3, 0)),
+    (gl::TEXTURE_2D_MULTISAMPLE, gl::TEXTURE_BINDING_2D_MULTISAMPLE, (3, 2)),
+    (gl::TEXTURE_2D_MULTISAMPLE_ARRAY, gl::TEXTURE_BINDING_2D_MULTISAMPLE_ARRAY, (3, 2)),
+    (gl::TEXTURE_3D, gl::TEXTURE_BINDING_3D, (1, 2)),
+    (gl::TEXTURE_BUFFER, gl::TEXTURE_BINDING_BUFFER, (3, 1)),
+    (gl::TEXTURE_CUBE_MAP, gl::TEXTURE_BINDING_CUBE_MAP, (1, 3)),
+    (gl::TEXTURE_CUBE_MAP_ARRAY, gl::TEXTURE_BINDING_CUBE_MAP_ARRAY, (4, 0)),
+    (gl::TEXTURE_RECTANGLE, gl::TEXTURE_BINDING_RECTANGLE, (3, 1)),
+];
+
+/// The state of the current context relevant for drawing, returned by `snapshot`.
+///
+/// Capabilities are `None` if they are disabled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub program:              types::Program,
+    pub vertex_array:         types::VertexArray,
+    pub draw_framebuffer:     types::Framebuffer,
+    pub read_framebuffer:     types::Framebuffer,
+    pub renderbuffer:         types::Renderbuffer,
+    /// The targets with a buffer bound.
+    pub buffers:              Vec<(Enum, types::Buffer)>,
+    /// The index of the active texture unit.
+    pub active_texture:       u32,
+    /// The texture units with a texture or sampler bound.
+    pub texture_units:        Vec<TextureUnit>,
+    pub viewport:             [i32; 4],
+    pub depth_range:          [f32; 2],
+    pub scissor:              Option<[i32; 4]>,
+    pub color_mask:           [bool; 4],
+    /// The blending of the first draw buffer.
+    pub blend:                Option<Blend>,
+    pub depth_test:           Option<Enum>,
+    pub depth_mask:           bool,
+    pub stencil:              Option<Stencil>,
+    pub cull_face:            Option<Enum>,
+    pub front_face:           Enum,
+    pub polygon_mode:         Enum,
+    /// The element array buffer of the vertex array.
+    pub element_array_buffer: types::Buffer,
+    /// The enabled attributes of the vertex array.
+    pub vertex_attribs:       Vec<VertexAttrib>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureUnit {
+    pub unit:     u32,
+    /// The targets with a texture bound.
+    pub textures: Vec<(Enum, types::Texture)>,
+    pub sampler:  types::Sampler,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blend {
+    pub src_rgb:        Enum,
+    pub dst_rgb:        Enum,
+    pub src_alpha:      Enum,
+    pub dst_alpha:      Enum,
+    pub equation_rgb:   Enum,
+    pub equation_alpha: Enum,
+    pub color:          [f32; 4],
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stencil {
+    pub front: StencilFace,
+    pub back:  StencilFace,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StencilFace {
+    pub func:       Enum,
+    pub reference:  i32,
+    pub value_mask: u32,
+    pub fail:       Enum,
+    pub depth_fail: Enum,
+    pub depth_pass: Enum,
+    pub write_mask: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexAttrib {
+    pub index:      u32,
+    pub buffer:     types::Buffer,
+    pub size:       i32,
+    pub ty:         Enum,
+    pub normalized: bool,
+    /// Whether the attribute is read as integers, as set by `VertexAttribIPointer`.
+    pub integer:    bool,
+    pub stride:     i32,
+    pub offset:     usize,
+    pub divisor:    u32,
+}
+
+/// A field which differs between two snapshots, returned by `Snapshot::diff`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// The path of the field, e.g. `blend` or `texture_units[0].TEXTURE_2D`.
+    pub field:  String,
+    /// The value in the first snapshot, or `None` if it had no entry for the field.
+    pub before: Option<String>,
+    /// The value in the second snapshot, or `None` if it had no entry for the field.
+    pub after:  Option<String>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+        write!(fmt, "{}: {} -> {}", self.field, value(&self.before), value(&self.after))
+    }
+}
+
+/// Queries the state of the current context relevant for drawing.
+///
+/// Switches the active texture unit to query the texture bindings of every unit, and restores it.
+///  Bindings which the version of the context doesn't have are left out.
+pub fn snapshot() -> Snapshot {
+    let version = (integer(gl::MAJOR_VERSION), integer(gl::MINOR_VERSION));
+    let active_texture = enum_(gl::ACTIVE_TEXTURE).0;
+    let units = integer(gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS) as u32;
+    let mut texture_units = Vec::new();
+    for unit in 0..units {
+        gl::ActiveTexture(gl::TEXTURE0 + unit);
+        let textures: Vec<_> = TEXTURE_TARGETS
+            .iter()
+            .filter(|&&(_, _, since)| since <= version)
+            .map(|&(target, binding, _)| (Enum(target), types::Texture(integer(binding) as types::GLuint)))
+            .filter(|&(_, texture)| texture.0 != 0)
+            .collect();
+        // sampler objects are from 3.3
+        let sampler = types::Sampler(if version >= (3, 3) { integer(gl::SAMPLER_BINDING) as types::GLuint } else { 0 });
+        if !textures.is_empty() || sampler.0 != 0 {
+            texture_units.push(TextureUnit { unit, textures, sampler });
+        }
+    }
+    gl::ActiveTexture(active_texture);
+
+    let attribs = integer(gl::MAX_VERTEX_ATTRIBS) as u32;
+    let vertex_attribs = (0..attribs).filter(|&index| attrib(index, gl::VERTEX_ATTRIB_ARRAY_ENABLED) != 0).map(|index| vertex_attrib(index, version)).collect();
+
+    let color_mask = {
+        let mut mask = [0; 4];
+        unsafe { gl::GetBooleanv(gl::COLOR_WRITEMASK, mask.as_mut_ptr()) };
+        [mask[0] != 0, mask[1] != 0, mask[2] != 0, mask[3] != 0]
+    };
+    let depth_range = {
+        let mut range = [0.0; 2];
+        unsafe { gl::GetFloatv(gl::DEPTH_RANGE, range.as_mut_ptr()) };
+        range
+    };
+
+    Snapshot {
+        program:              types::Program(integer(gl::CURRENT_PROGRAM) as types::GLuint),
+        vertex_array:         types::VertexArray(integer(gl::VERTEX_ARRAY_BINDING) as types::GLuint),
+        draw_framebuffer:     types::Framebuffer(integer(gl::DRAW_FRAMEBUFFER_BINDING) as types::GLuint),
+        read_framebuffer:     types::Framebuffer(integer(gl::READ_FRAMEBUFFER_BINDING) as types::GLuint),
+        renderbuffer:         types::Renderbuffer(integer(gl::RENDERBUFFER_BINDING) as types::GLuint),
+        buffers:              BUFFER_TARGETS
+            .iter()
+            .filter(|&&(_, _, since)| since <= version)
+            .map(|&(target, binding, _)| (Enum(target), types::Buffer(integer(binding) as types::GLuint)))
+            .filter(|&(_, buffer)| buffer.0 != 0)
+            .collect(),
+        active_texture:       active_texture.saturating_sub(gl::TEXTURE0),
+        texture_units,
+        viewport:             integers(gl::VIEWPORT),
+        depth_range,
+        scissor:              enabled(gl::SCISSOR_TEST).then(|| integers(gl::SCISSOR_BOX)),
+        color_mask,
+        blend:                enabled(gl::BLEND).then(blend),
+        depth_test:           enabled(gl::DEPTH_TEST).then(|| enum_(gl::DEPTH_FUNC)),
+        depth_mask:           integer(gl::DEPTH_WRITEMASK) != 0,
+        stencil:              enabled(gl::STENCIL_TEST).then(stencil),
+        cull_face:            enabled(gl::CULL_FACE).then(|| enum_(gl::CULL_FACE_MODE)),
+        front_face:           enum_(gl::FRONT_FACE),
+        // some drivers still return the modes of the front and back faces
+        polygon_mode:         Enum(integers(gl::POLYGON_MODE)[0] as types::GLenum),
+        element_array_buffer: types::Buffer(integer(gl::ELEMENT_ARRAY_BUFFER_BINDING) as types::GLuint),
+        vertex_attribs,
+    }
+}
+
+impl Snapshot {
+    /// Returns the fields which differ from `other`, with the bindings and vertex attributes
+    ///  compared by target, unit and index.
+    pub fn diff(&self, other: &Snapshot) -> Vec<Change> {
+        let (before, after) = (self.entries(), other.entries());
+        let mut changes = Vec::new();
+        for (field, value) in &before {
+            let other = after.iter().find(|(other, _)| other == field).map(|(_, value)| value);
+            if other != Some(value) {
+                changes.push(Change {
+                    field:  field.clone(),
+                    before: Some(value.clone()),
+                    after:  other.cloned(),
+                });
+            }
+        }
+        for (field, value) in &after {
+            if !before.iter().any(|(other, _)| other == field) {
+                changes.push(Change {
+                    field:  field.clone(),
+                    before: None,
+                    after:  Some(value.clone()),
+                });
+            }
+        }
+        changes
+    }
+
+    /// Returns the paths and values of the fields, with one entry per binding and attribute.
+    fn entries(&self) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        let mut entry = |field: String, value: &dyn fmt::Debug| entries.push((field, format!("{:?}", value)));
+        entry("program".to_string(), &self.program);
+        entry("vertex_array".to_string(), &self.vertex_array);
+        entry("draw_framebuffer".to_string(), &self.draw_framebuffer);
+        entry("read_framebuffer".to_string(), &self.read_framebuffer);
+        entry("renderbuffer".to_string(), &self.renderbuffer);
+        for (target, buffer) in &self.buffers {
+            entry(format!("buffers[{:?}]", target), buffer);
+        }
+        entry("active_texture".to_string(), &self.active_texture);
+        for unit in &self.texture_units {
+            for (target, texture) in &unit.textures {
+                entry(format!("texture_units[{}].{:?}", unit.unit, target), texture);
+            }
+            if unit.sampler.0 != 0 {
+                entry(format!("texture_units[{}].sampler", unit.unit), &unit.sampler);
+            }
+        }
+        entry("viewport".to_string(), &self.viewport);
+        entry("depth_range".to_string(), &self.depth_range);
+        entry("scissor".to_string(), &self.scissor);
+        entry("color_mask".to_string(), &self.color_mask);
+        entry("blend".to_string(), &self.blend);
+        entry("depth_test".to_string(), &self.depth_test);
+        entry("depth_mask".to_string(), &self.depth_mask);
+        entry("stencil".to_string(), &self.stencil);
+        entry("cull_face".to_string(), &self.cull_face);
+        entry("front_face".to_string(), &self.front_face);
+        entry("polygon_mode".to_string(), &self.polygon_mode);
+        entry("element_array_buffer".to_string(), &self.element_array_buffer);
+        for attrib in &self.vertex_attribs {
+            entry(format!("vertex_attribs[{}]", attrib.index), attrib);
+        }
+        entries
+    }
+}
+
+fn integer(pname: types::GLenum) -> types::GLint {
+    // only queried for state with a single value
+    unsafe { gl::GetIntegerv(pname) }
+}
+
+fn enum_(pname: types::GLenum) -> Enum {
+    Enum(integer(pname) as types::GLenum)
+}
+
+/// Queries state with up to 4 integer values.
+fn integers(pname: types::GLenum) -> [i32; 4] {
+    let mut values = [0; 4];
+    unsafe { gl::GetInteger64v(pname, values.as_mut_ptr()) };
+    [values[0] as i32, values[1] as i32, values[2] as i32, values[3] as i32]
+}
+
+fn enabled(cap: types::GLenum) -> bool {
+    gl::IsEnabled(cap) != 0
+}
+
+fn blend() -> Blend {
+    let mut color = [0.0; 4];
+    unsafe { gl::GetFloatv(gl::BLEND_COLOR, color.as_mut_ptr()) };
+    Blend {
+        src_rgb:        enum_(gl::BLEND_SRC_RGB),
+        dst_rgb:        enum_(gl::BLEND_DST_RGB),
+        src_alpha:      enum_(gl::BLEND_SRC_ALPHA),
+        dst_alpha:      enum_(gl::BLEND_DST_ALPHA),
+        equation_rgb:   enum_(gl::BLEND_EQUATION_RGB),
+        equation_alpha: enum_(gl::BLEND_EQUATION_ALPHA),
+        color,
+    }
+}
+
+fn stencil() -> Stencil {
+    Stencil {
+        front: StencilFace {
+            func:       enum_(gl::STENCIL_FUNC),
+            reference:  integer(gl::STENCIL_REF),
+            value_mask: integer(gl::STENCIL_VALUE_MASK) as u32,
+            fail:       enum_(gl::STENCIL_FAIL),
+            depth_fail: enum_(gl::STENCIL_PASS_DEPTH_FAIL),
+            depth_pass: enum_(gl::STENCIL_PASS_DEPTH_PASS),
+            write_mask: integer(gl::STENCIL_WRITEMASK) as u32,
+        },
+        back:  StencilFace {
+            func:       enum_(gl::STENCIL_BACK_FUNC),
+            reference:  integer(gl::STENCIL_BACK_REF),
+            value_mask: integer(gl::STENCIL_BACK_VALUE_MASK) as u32,
+            fail:       enum_(gl::STENCIL_BACK_FAIL),
+            depth_fail: enum_(gl::STENCIL_BACK_PASS_DEPTH_FAIL),
+            depth_pass: enum_(gl::STENCIL_BACK_PASS_DEPTH_PASS),
+            write_mask: integer(gl::STENCIL_BACK_WRITEMASK) as u32,
+        },
+    }
+}
+
+fn attrib(index: types::GLuint, pname: types::GLenum) -> types::GLint {
+    // only queried for state with a single value
+    unsafe { gl::GetVertexAttribiv(index, pname) }
+}
+
+fn vertex_attrib(index: types::GLuint, version: (i32, i32)) -> VertexAttrib {
+    let mut offset = ptr::null_mut();
+    unsafe { gl::GetVertexAttribPointerv(index, gl::VERTEX_ATTRIB_ARRAY_POINTER, &mut offset) };
+    VertexAttrib {
+        index,
+        buffer:     types::Buffer(attrib(index, gl::VERTEX_ATTRIB_ARRAY_BUFFER_BINDING) as types::GLuint),
+        size:       attrib(index, gl::VERTEX_ATTRIB_ARRAY_SIZE),
+        ty:         Enum(attrib(index, gl::VERTEX_ATTRIB_ARRAY_TYPE) as types::GLenum),
+        normalized: attrib(index, gl::VERTEX_ATTRIB_ARRAY_NORMALIZED) != 0,
+        integer:    attrib(index, gl::VERTEX_ATTRIB_ARRAY_INTEGER) != 0,
+        stride:     attrib(index, gl::VERTEX_ATTRIB_ARRAY_STRIDE),
+        offset:     offset as usize,
+        // instanced arrays are from 3.3
+        divisor:    if version >= (3, 3) { attrib(index, gl::VERTEX_ATTRIB_ARRAY_DIVISOR) as u32 } else { 0 },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            program:              types::Program(1),
+            vertex_array:         types::VertexArray(1),
+            draw_framebuffer:     types::Framebuffer(0),
+            read_framebuffer:     types::Framebuffer(0),
+            renderbuffer:         types::Renderbuffer(0),
+            buffers:              vec![(Enum(gl::ARRAY_BUFFER), types::Buffer(2))],
+            active_texture:       0,
+            texture_units:        vec![TextureUnit {
+                unit:     0,
+                textures: vec![(Enum(gl::TEXTURE_2D), types::Texture(3))],
+                sampler:  types::Sampler(0),
+            }],
+            viewport:             [0, 0, 800, 600],
+            depth_range:          [0.0, 1.0],
+            scissor:              None,
+            color_mask:           [true; 4],
+            blend:                None,
+            depth_test:           Some(Enum(gl::LESS)),
+            depth_mask:           true,
+            stencil:              None,
+            cull_face:            Some(Enum(gl::BACK)),
+            front_face:           Enum(gl::CCW),
+            polygon_mode:         Enum(gl::FILL),
+            element_array_buffer: types::Buffer(0),
+            vertex_attribs:       Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_enum_debug() {
+        assert_eq!(format!("{:?}", Enum(gl::ONE_MINUS_SRC_ALPHA)), "ONE_MINUS_SRC_ALPHA");
+        assert_eq!(format!("{:?}", Enum(0)), "ZERO");
+        assert_eq!(format!("{:?}", Enum(0x1234)), "0x1234");
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = snapshot();
+        assert!(before.diff(&before).is_empty());
+
+        let mut after = snapshot();
+        after.texture_units[0].textures[0].0 = Enum(gl::TEXTURE_3D);
+        after.cull_face = None;
+        let changes: Vec<_> = before.diff(&after).iter().map(|change| change.to_string()).collect();
+        assert_eq!(changes, [
+            "texture_units[0].TEXTURE_2D: Texture(3) -> -",
+            "cull_face: Some(BACK) -> None",
+            "texture_units[0].TEXTURE_3D: - -> Texture(3)",
+        ]);
+    }
+}
@@ -0,0 +1,32 @@
+//! `gl::stats` against stub functions, which stand in for a GL context.
+
+#![cfg(feature = "call_stats")]
+
+use gl::types::{GLbitfield, GLenum, GLint, GLsizei, GLsync};
+use std::os::raw::c_void;
+
+extern "system" fn draw_arrays(_mode: GLenum, _first: GLint, _count: GLsizei) {}
+
+extern "system" fn fence_sync(_condition: GLenum, _flags: GLbitfield) -> GLsync {
+    std::ptr::dangling_mut()
+}
+
+#[test]
+fn test_call_stats() {
+    gl::load_with(|symbol| match symbol {
+        "glDrawArrays" => draw_arrays as *const c_void,
+        "glFenceSync" => fence_sync as *const c_void,
+        _ => std::ptr::null(),
+    });
+    let calls = || gl::stats::snapshot().into_iter().map(|(name, stats)| (name, stats.calls)).collect::<Vec<_>>();
+
+    for _ in 0..3 {
+        gl::DrawArrays(gl::TRIANGLES, 0, 3);
+    }
+    // called by `Fence::new`
+    std::mem::forget(gl::Fence::new());
+    assert_eq!(calls(), [("DrawArrays", 3), ("FenceSync", 1)]);
+
+    gl::stats::reset();
+    assert_eq!(calls(), []);
+}
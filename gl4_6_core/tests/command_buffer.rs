@@ -0,0 +1,52 @@
+//! `CommandBuffer` against stub functions, which stand in for a GL context.
+
+#![cfg(feature = "command_buffer")]
+
+use gl::types::{GLenum, GLint, GLsizei, GLsizeiptr, GLuint};
+use std::os::raw::c_void;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+static DRAWN: AtomicUsize = AtomicUsize::new(0);
+static BUFFER_DATA: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+extern "system" fn draw_arrays(_mode: GLenum, _first: GLint, count: GLsizei) {
+    DRAWN.fetch_add(count as usize, Ordering::SeqCst);
+}
+
+extern "system" fn buffer_data(_target: GLenum, size: GLsizeiptr, data: *const c_void, _usage: GLenum) {
+    let data = unsafe { std::slice::from_raw_parts(data as *const u8, size as usize) };
+    BUFFER_DATA.lock().unwrap().extend_from_slice(data);
+}
+
+extern "system" fn bind_buffer(_target: GLenum, _buffer: GLuint) {}
+
+#[test]
+fn test_command_buffer() {
+    gl::load_with(|symbol| match symbol {
+        "glDrawArrays" => draw_arrays as *const c_void,
+        "glBufferData" => buffer_data as *const c_void,
+        "glBindBuffer" => bind_buffer as *const c_void,
+        _ => std::ptr::null(),
+    });
+
+    let vertices = [1u16, 2, 3];
+    let cmds = std::thread::spawn(move || {
+        let mut cmds = gl::CommandBuffer::new();
+        cmds.BindBuffer(gl::ARRAY_BUFFER, gl::types::Buffer(1));
+        cmds.BufferData(gl::ARRAY_BUFFER, &vertices, gl::STATIC_DRAW);
+        cmds.DrawArrays(gl::TRIANGLES, 0, 3);
+        cmds
+    })
+    .join()
+    .unwrap();
+    assert_eq!(cmds.len(), 3);
+    // nothing is called until executed
+    assert_eq!(DRAWN.load(Ordering::SeqCst), 0);
+
+    cmds.execute();
+    assert_eq!(DRAWN.load(Ordering::SeqCst), 3);
+    assert_eq!(*BUFFER_DATA.lock().unwrap(), vertices.iter().flat_map(|v| v.to_ne_bytes()).collect::<Vec<_>>());
+    cmds.execute();
+    assert_eq!(DRAWN.load(Ordering::SeqCst), 6);
+}
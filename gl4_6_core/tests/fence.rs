@@ -0,0 +1,57 @@
+//! `Fence` against stub functions, which stand in for a GL context.
+
+use gl::types::{GLbitfield, GLenum, GLint, GLsizei, GLsync, GLuint64};
+use std::os::raw::c_void;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::time::Duration;
+
+static WAIT_STATUS: AtomicU32 = AtomicU32::new(0);
+static DELETED_SYNCS: AtomicUsize = AtomicUsize::new(0);
+
+extern "system" fn fence_sync(_condition: GLenum, _flags: GLbitfield) -> GLsync {
+    std::ptr::dangling_mut()
+}
+
+extern "system" fn client_wait_sync(_sync: GLsync, _flags: GLbitfield, _timeout: GLuint64) -> GLenum {
+    WAIT_STATUS.load(Ordering::SeqCst)
+}
+
+extern "system" fn get_synciv(_sync: GLsync, _pname: GLenum, _count: GLsizei, _length: *mut GLsizei, values: *mut GLint) {
+    unsafe { *values = gl::SIGNALED as GLint };
+}
+
+extern "system" fn delete_sync(_sync: GLsync) {
+    DELETED_SYNCS.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn test_fence() {
+    gl::load_with(|symbol| match symbol {
+        "glFenceSync" => fence_sync as *const c_void,
+        "glClientWaitSync" => client_wait_sync as *const c_void,
+        "glGetSynciv" => get_synciv as *const c_void,
+        "glDeleteSync" => delete_sync as *const c_void,
+        _ => std::ptr::null(),
+    });
+
+    let fence = gl::Fence::new().unwrap();
+    let statuses = [
+        (gl::ALREADY_SIGNALED, Ok(gl::FenceStatus::AlreadySignaled)),
+        (gl::CONDITION_SATISFIED, Ok(gl::FenceStatus::Signaled)),
+        (gl::TIMEOUT_EXPIRED, Ok(gl::FenceStatus::TimedOut)),
+        (gl::WAIT_FAILED, Err(gl::WaitFailed)),
+    ];
+    for (status, expected) in statuses {
+        WAIT_STATUS.store(status, Ordering::SeqCst);
+        assert_eq!(fence.wait(Duration::from_secs(u64::MAX)), expected);
+    }
+    assert!(fence.is_signaled());
+    drop(fence);
+    assert_eq!(DELETED_SYNCS.load(Ordering::SeqCst), 1);
+
+    // owned by the caller
+    let sync = gl::Fence::new().unwrap().into_raw();
+    assert_eq!(DELETED_SYNCS.load(Ordering::SeqCst), 1);
+    drop(unsafe { gl::Fence::from_raw(sync) });
+    assert_eq!(DELETED_SYNCS.load(Ordering::SeqCst), 2);
+}
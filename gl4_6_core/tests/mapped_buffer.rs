@@ -0,0 +1,67 @@
+//! `MappedBuffer` against stub functions, which stand in for a GL context.
+
+use gl::types::{GLbitfield, GLboolean, GLenum, GLint64, GLintptr, GLsizeiptr};
+use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU32, AtomicU8, AtomicUsize, Ordering};
+
+// the data store of the mapped buffers
+static MAPPED: [AtomicU32; 4] = [AtomicU32::new(1), AtomicU32::new(2), AtomicU32::new(3), AtomicU32::new(4)];
+static UNMAPS: AtomicUsize = AtomicUsize::new(0);
+static UNMAPPED: AtomicU8 = AtomicU8::new(1);
+
+extern "system" fn get_buffer_parameteri64v(_target: GLenum, _pname: GLenum, params: *mut GLint64) {
+    // two `u32`s and two bytes
+    unsafe { *params = 10 };
+}
+
+extern "system" fn map_buffer(_target: GLenum, _access: GLenum) -> *mut c_void {
+    MAPPED.as_ptr() as *mut c_void
+}
+
+extern "system" fn map_buffer_range(_target: GLenum, _offset: GLintptr, length: GLsizeiptr, _access: GLbitfield) -> *mut c_void {
+    match length {
+        0 => std::ptr::null_mut(),
+        _ => MAPPED.as_ptr() as *mut c_void,
+    }
+}
+
+extern "system" fn unmap_buffer(_target: GLenum) -> GLboolean {
+    UNMAPS.fetch_add(1, Ordering::SeqCst);
+    UNMAPPED.load(Ordering::SeqCst)
+}
+
+#[test]
+fn test_mapped_buffer() {
+    gl::load_with(|symbol| match symbol {
+        "glGetBufferParameteri64v" => get_buffer_parameteri64v as *const c_void,
+        "glMapBuffer" => map_buffer as *const c_void,
+        "glMapBufferRange" => map_buffer_range as *const c_void,
+        "glUnmapBuffer" => unmap_buffer as *const c_void,
+        _ => std::ptr::null(),
+    });
+
+    let mut mapped = unsafe { gl::MapBuffer::<u32>(gl::ARRAY_BUFFER, gl::READ_WRITE) }.unwrap();
+    assert_eq!(mapped.as_slice(), [1, 2]);
+    mapped.as_mut_slice()[1] = 5;
+    assert_eq!(mapped.unmap(), Ok(()));
+    assert_eq!(MAPPED[1].load(Ordering::SeqCst), 5);
+    assert_eq!(UNMAPS.load(Ordering::SeqCst), 1);
+
+    let mut mapped = unsafe { gl::MapBufferRange::<u16>(gl::ARRAY_BUFFER, 0, 6, gl::MAP_READ_BIT) }.unwrap();
+    assert_eq!(mapped.len(), 3);
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| mapped.as_mut_slice().len())).is_err());
+    drop(mapped);
+    assert_eq!(UNMAPS.load(Ordering::SeqCst), 2);
+
+    let mut mapped = unsafe { gl::MapBuffer::<u32>(gl::ARRAY_BUFFER, gl::WRITE_ONLY) }.unwrap();
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| mapped.as_slice().len())).is_err());
+    assert_eq!(mapped.as_mut_slice().len(), 2);
+    UNMAPPED.store(0, Ordering::SeqCst);
+    assert_eq!(mapped.unmap(), Err(gl::DataCorrupted));
+    UNMAPPED.store(1, Ordering::SeqCst);
+
+    // nothing is mapped
+    assert!(unsafe { gl::MapBufferRange::<u32>(gl::ARRAY_BUFFER, 0, 0, gl::MAP_READ_BIT) }.is_none());
+    assert_eq!(UNMAPS.load(Ordering::SeqCst), 3);
+}
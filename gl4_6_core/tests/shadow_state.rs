@@ -0,0 +1,60 @@
+//! `gl::shadow` against stub functions, which stand in for a GL context.
+
+#![cfg(feature = "shadow_state")]
+
+use gl::types::{Buffer, GLenum, GLuint, Program, VertexArray};
+use std::os::raw::c_void;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static USED_PROGRAMS: AtomicUsize = AtomicUsize::new(0);
+static BOUND_BUFFERS: AtomicUsize = AtomicUsize::new(0);
+
+extern "system" fn use_program(_program: GLuint) {
+    USED_PROGRAMS.fetch_add(1, Ordering::SeqCst);
+}
+
+extern "system" fn bind_buffer(_target: GLenum, _buffer: GLuint) {
+    BOUND_BUFFERS.fetch_add(1, Ordering::SeqCst);
+}
+
+extern "system" fn stub(_name: GLuint) {}
+
+#[test]
+fn test_shadow_state() {
+    gl::load_with(|symbol| match symbol {
+        "glUseProgram" => use_program as *const c_void,
+        "glBindBuffer" => bind_buffer as *const c_void,
+        "glDeleteProgram" | "glBindVertexArray" => stub as *const c_void,
+        _ => std::ptr::null(),
+    });
+
+    gl::UseProgram(Program(1));
+    gl::UseProgram(Program(1));
+    assert_eq!(USED_PROGRAMS.load(Ordering::SeqCst), 1);
+    assert_eq!(gl::shadow::skipped(), [("UseProgram", 1)]);
+    gl::UseProgram(Program(2));
+    assert_eq!(USED_PROGRAMS.load(Ordering::SeqCst), 2);
+
+    // the program may have been current
+    gl::DeleteProgram(Program(2));
+    gl::UseProgram(Program(2));
+    assert_eq!(USED_PROGRAMS.load(Ordering::SeqCst), 3);
+    gl::shadow::invalidate();
+    gl::UseProgram(Program(2));
+    assert_eq!(USED_PROGRAMS.load(Ordering::SeqCst), 4);
+    // the state is kept per thread
+    std::thread::spawn(|| gl::UseProgram(Program(2))).join().unwrap();
+    assert_eq!(USED_PROGRAMS.load(Ordering::SeqCst), 5);
+
+    // the element array buffer binding is part of the vertex array
+    gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, Buffer(1));
+    gl::BindBuffer(gl::ARRAY_BUFFER, Buffer(1));
+    gl::BindVertexArray(VertexArray(1));
+    gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, Buffer(1));
+    gl::BindBuffer(gl::ARRAY_BUFFER, Buffer(1));
+    assert_eq!(BOUND_BUFFERS.load(Ordering::SeqCst), 3);
+    assert_eq!(gl::shadow::skipped(), [("BindBuffer", 1), ("UseProgram", 1)]);
+
+    gl::shadow::reset_skipped();
+    assert_eq!(gl::shadow::skipped(), []);
+}
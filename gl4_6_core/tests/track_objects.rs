@@ -0,0 +1,51 @@
+//! `gl::objects` against stub functions, which stand in for a GL context.
+
+#![cfg(all(feature = "track_objects", debug_assertions))]
+
+use gl::types::{Buffer, GLenum, GLsizei, GLuint};
+use std::os::raw::c_void;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static NAMES: AtomicU32 = AtomicU32::new(0);
+
+extern "system" fn gen_buffers(n: GLsizei, buffers: *mut GLuint) {
+    for i in 0..n as usize {
+        unsafe { *buffers.add(i) = NAMES.fetch_add(1, Ordering::SeqCst) + 1 };
+    }
+}
+
+extern "system" fn delete_buffers(_n: GLsizei, _buffers: *const GLuint) {}
+
+extern "system" fn create_shader(_type: GLenum) -> GLuint {
+    NAMES.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+extern "system" fn delete_shader(_shader: GLuint) {}
+
+#[test]
+fn test_track_objects() {
+    gl::load_with(|symbol| match symbol {
+        "glGenBuffers" => gen_buffers as *const c_void,
+        "glDeleteBuffers" => delete_buffers as *const c_void,
+        "glCreateShader" => create_shader as *const c_void,
+        "glDeleteShader" => delete_shader as *const c_void,
+        _ => std::ptr::null(),
+    });
+    let live = || gl::objects::live().into_iter().map(|object| (object.kind, object.name)).collect::<Vec<_>>();
+
+    let mut buffers = [Buffer(0); 2];
+    gl::slices::GenBuffers(&mut buffers);
+    let shader = gl::CreateShader(gl::VERTEX_SHADER);
+    let buffer = gl::GenBuffers();
+    // in the order of their creation
+    assert_eq!(live(), [("Buffer", 1), ("Buffer", 2), ("Shader", 3), ("Buffer", 4)]);
+    assert!(gl::objects::live().iter().all(|object| !object.created.is_empty()));
+
+    gl::DeleteShader(shader);
+    gl::slices::DeleteBuffers(&buffers[..1]);
+    assert_eq!(live(), [("Buffer", 2), ("Buffer", buffer.0)]);
+    assert_eq!(gl::objects::report(), 2);
+
+    gl::objects::clear();
+    assert_eq!(live(), []);
+}
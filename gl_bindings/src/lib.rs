@@ -0,0 +1,223 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+//! The `gl_bindings!` macro generates OpenGL bindings in place, using the same registry and
+//! generators as a `gl_generator` build script.
+//!
+//! # Example
+//!
+//! ```ignore
+//! mod gl {
+//!     gl_bindings::gl_bindings! {
+//!         api: Gl,
+//!         version: 4.6,
+//!         profile: Core,
+//!         extensions: ["GL_EXT_texture_filter_anisotropic"],
+//!         generator: GlobalTyped,
+//!     }
+//! }
+//!
+//! gl::load_with(|s| window.get_proc_address(s) as *const _);
+//! ```
+//!
+//! Only `api` and `version` are required. The other keys default to `profile: Core`,
+//!  `extensions: []`, `fallbacks: All`, `debug_prints: None` and `generator: GlobalTyped`.
+//!
+//! The bindings are placed in a private `__gl_bindings` module and re-exported, so invoke the
+//!  macro at most once per module. Parsing the registry is cached like in build scripts, see
+//!  `GL_GENERATOR_CACHE_DIR`.
+
+extern crate proc_macro;
+
+use gl_generator::{Api, DebugPrints, DebugStructGenerator, Fallbacks, GlobalTypedGenerator, Profile, RegistryBuilder};
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+type Error = (Span, String);
+
+#[derive(Copy, Clone)]
+enum GeneratorKind {
+    GlobalTyped,
+    DebugStruct,
+}
+
+struct Config {
+    api:          Api,
+    version:      (u8, u8),
+    profile:      Option<Profile>,
+    fallbacks:    Fallbacks,
+    extensions:   Vec<String>,
+    debug_prints: DebugPrints,
+    generator:    GeneratorKind,
+}
+
+#[proc_macro]
+pub fn gl_bindings(input: TokenStream) -> TokenStream {
+    match Config::parse(input).and_then(|config| config.expand()) {
+        Ok(tokens) => tokens,
+        Err((span, msg)) => compile_error(span, &msg),
+    }
+}
+
+impl Config {
+    fn parse(input: TokenStream) -> Result<Config, Error> {
+        let mut api = None;
+        let mut version = None;
+        let mut config = Config {
+            api:          Api::Gl,
+            version:      (0, 0),
+            profile:      None,
+            fallbacks:    Fallbacks::All,
+            extensions:   Vec::new(),
+            debug_prints: DebugPrints::None,
+            generator:    GeneratorKind::GlobalTyped,
+        };
+
+        let mut tokens = input.into_iter();
+        while let Some(token) = tokens.next() {
+            let key = match token {
+                TokenTree::Ident(key) => key,
+                other => return Err((other.span(), "expected a key such as `api`".to_string())),
+            };
+            match tokens.next() {
+                Some(TokenTree::Punct(ref punct)) if punct.as_char() == ':' => (),
+                _ => return Err((key.span(), format!("expected `:` after `{}`", key))),
+            }
+            let value = tokens.next().ok_or_else(|| (key.span(), format!("missing value for `{}`", key)))?;
+
+            match &*key.to_string() {
+                "api" => {
+                    api = Some(parse_ident(&value, &[
+                        ("Gl", Api::Gl),
+                        ("Glx", Api::Glx),
+                        ("Wgl", Api::Wgl),
+                        ("Egl", Api::Egl),
+                        ("GlCore", Api::GlCore),
+                        ("Gles1", Api::Gles1),
+                        ("Gles2", Api::Gles2),
+                        ("Glsc2", Api::Glsc2),
+                    ])?)
+                },
+                "version" => version = Some(parse_version(&value)?),
+                "profile" => config.profile = Some(parse_ident(&value, &[("Core", Profile::Core), ("Compatibility", Profile::Compatibility)])?),
+                "fallbacks" => config.fallbacks = parse_ident(&value, &[("All", Fallbacks::All), ("None", Fallbacks::None)])?,
+                "extensions" => config.extensions = parse_strings(&value)?,
+                "debug_prints" => {
+                    config.debug_prints = parse_ident(&value, &[("FunctionCalls", DebugPrints::FunctionCalls), ("None", DebugPrints::None)])?
+                },
+                "generator" => {
+                    config.generator = parse_ident(&value, &[
+                        ("GlobalTyped", GeneratorKind::GlobalTyped),
+                        ("DebugStruct", GeneratorKind::DebugStruct),
+                    ])?
+                },
+                _ => return Err((key.span(), format!("unknown key `{}`", key))),
+            }
+
+            match tokens.next() {
+                None => break,
+                Some(TokenTree::Punct(ref punct)) if punct.as_char() == ',' => (),
+                Some(other) => return Err((other.span(), "expected `,`".to_string())),
+            }
+        }
+
+        match (api, version) {
+            (Some(api), Some(version)) => {
+                config.api = api;
+                config.version = version;
+                Ok(config)
+            },
+            (None, _) => Err((Span::call_site(), "missing `api`".to_string())),
+            (_, None) => Err((Span::call_site(), "missing `version`".to_string())),
+        }
+    }
+
+    fn expand(self) -> Result<TokenStream, Error> {
+        let mut builder = RegistryBuilder::new(self.api, self.version)
+            .fallbacks(self.fallbacks)
+            .extensions(&self.extensions)
+            .debug_prints(self.debug_prints);
+        if let Some(profile) = self.profile {
+            builder = builder.profile(profile);
+        }
+        let registry = builder.build().map_err(|err| (Span::call_site(), err.to_string()))?;
+
+        let mut src = Vec::new();
+        match self.generator {
+            GeneratorKind::GlobalTyped => registry.write_bindings(GlobalTypedGenerator, &mut src),
+            GeneratorKind::DebugStruct => registry.write_bindings(DebugStructGenerator, &mut src),
+        }
+        .map_err(|err| (Span::call_site(), format!("Could not write bindings: {}", err)))?;
+        let src = String::from_utf8(src).map_err(|err| (Span::call_site(), format!("Bindings are not valid UTF-8: {}", err)))?;
+
+        // the bindings may start with inner attributes, which are only allowed at the start of a module
+        format!("mod __gl_bindings {{ {} }} pub use self::__gl_bindings::*;", src)
+            .parse()
+            .map_err(|err| (Span::call_site(), format!("Generated bindings do not parse: {:?}", err)))
+    }
+}
+
+fn parse_ident<T: Copy>(value: &TokenTree, options: &[(&str, T)]) -> Result<T, Error> {
+    let name = match value {
+        TokenTree::Ident(ident) => ident.to_string(),
+        _ => String::new(),
+    };
+    match options.iter().find(|&&(option, _)| option == name) {
+        Some(&(_, value)) => Ok(value),
+        None => {
+            let options = options.iter().map(|&(option, _)| format!("`{}`", option)).collect::<Vec<_>>();
+            Err((value.span(), format!("expected one of {}", options.join(", "))))
+        },
+    }
+}
+
+/// Parses a version literal such as `4.6`.
+fn parse_version(value: &TokenTree) -> Result<(u8, u8), Error> {
+    let version = value.to_string();
+    let mut parts = version.splitn(2, '.');
+    match (parts.next().map(str::parse), parts.next().map(str::parse)) {
+        (Some(Ok(major)), Some(Ok(minor))) => Ok((major, minor)),
+        _ => Err((value.span(), "expected a version such as `4.6`".to_string())),
+    }
+}
+
+/// Parses a list of string literals such as `["GL_ARB_foo", "GL_EXT_bar"]`.
+fn parse_strings(value: &TokenTree) -> Result<Vec<String>, Error> {
+    let group = match value {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Bracket => group,
+        _ => return Err((value.span(), "expected a list such as `[\"GL_ARB_foo\"]`".to_string())),
+    };
+
+    let mut strings = Vec::new();
+    for token in group.stream() {
+        match token {
+            TokenTree::Punct(ref punct) if punct.as_char() == ',' => (),
+            other => match syn::parse::<syn::LitStr>(other.clone().into()) {
+                Ok(literal) => strings.push(literal.value()),
+                Err(_) => return Err((other.span(), "expected a string literal".to_string())),
+            },
+        }
+    }
+    Ok(strings)
+}
+
+/// Expands to `compile_error!("{msg}");`, pointing at `span`.
+fn compile_error(span: Span, msg: &str) -> TokenStream {
+    let mut message = TokenTree::Literal(Literal::string(msg));
+    message.set_span(span);
+    let tokens = vec![
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, message.into())),
+        TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+    ];
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
@@ -0,0 +1,42 @@
+#[allow(clippy::all)]
+mod gl {
+    gl_bindings::gl_bindings! {
+        api: Gl,
+        version: 3.3,
+        profile: Core,
+        extensions: ["GL_EXT_texture_filter_anisotropic"],
+        generator: GlobalTyped,
+    }
+}
+
+#[allow(clippy::all)]
+mod gl_struct {
+    gl_bindings::gl_bindings! {
+        api: Gl,
+        version: 2.1,
+        fallbacks: None,
+        // raw and escaped string literals are unescaped
+        extensions: [r"GL_EXT_texture_filter_anisotropic", "GL_EXT_\x64raw_buffers2"],
+        generator: DebugStruct,
+    }
+}
+
+#[test]
+fn test_enums_and_types() {
+    assert_eq!(gl::TRIANGLES, 0x0004);
+    assert_eq!(gl::MAX_TEXTURE_MAX_ANISOTROPY_EXT, 0x84FF);
+    let _: gl::types::GLuint = gl::TRIANGLES;
+    assert_eq!(gl_struct::MAX_TEXTURE_MAX_ANISOTROPY_EXT, 0x84FF);
+}
+
+#[test]
+fn test_version_filter() {
+    gl::load_with(|symbol| if symbol == "glVertexAttribDivisor" { std::ptr::dangling() } else { std::ptr::null() });
+    assert!(gl::is_loaded("VertexAttribDivisor"));
+    assert!(!gl::is_loaded("DrawArrays"));
+    assert!(!gl::is_loaded("NotACommand"));
+
+    let gl = gl_struct::Gl::load_with(|_| std::ptr::null());
+    assert!(!gl.DrawArrays.is_loaded());
+    assert!(!gl.ColorMaskIndexedEXT.is_loaded());
+}
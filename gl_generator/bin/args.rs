@@ -0,0 +1,27 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+//! Argument parsing shared by the command-line tools.
+
+/// Parses a version of the form `4.6`.
+pub fn parse_version(src: &str) -> Result<(u8, u8), String> {
+    let mut parts = src.splitn(2, '.');
+    match (parts.next().map(str::parse), parts.next().map(str::parse)) {
+        (Some(Ok(major)), Some(Ok(minor))) => Ok((major, minor)),
+        _ => Err(format!("invalid version `{}`, expected e.g. `4.6`", src)),
+    }
+}
+
+/// Splits a comma-separated list such as `GL_ARB_foo,GL_EXT_bar`.
+pub fn parse_list(src: &str) -> Vec<String> {
+    src.split(',').map(str::trim).filter(|e| !e.is_empty()).map(str::to_string).collect()
+}
+
+/// Returns the value following the option `option`.
+pub fn value<I>(args: &mut I, option: &str) -> Result<String, String>
+where I: Iterator<Item = String> {
+    args.next().ok_or_else(|| format!("missing value for `{}`", option))
+}
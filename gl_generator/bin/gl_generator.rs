@@ -0,0 +1,147 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+//! Generates bindings outside of a build script, e.g. to vendor them into a crate.
+//!
+//! ```text
+//! gl_generator --api gl --version 4.6 --profile core \
+//!     --extensions GL_EXT_texture_filter_anisotropic,GL_ARB_draw_buffers_blend \
+//!     --generator global-typed --debug-prints calls -o bindings.rs
+//! ```
+
+extern crate gl_generator;
+
+use gl_generator::{Api, CallStats, DebugPrints, DebugStructGenerator, Fallbacks, GlobalTypedGenerator, Profile, RegistryBuilder};
+use std::{env,
+          fs::File,
+          io::{self, BufWriter},
+          process};
+
+mod args;
+
+const USAGE: &str = "usage: gl_generator --api <api> --version <major>.<minor> [--profile core|compatibility]
+                   [--extensions <ext>,...] [--fallbacks all|none]
+                   [--generator global-typed|debug-struct] [--debug-prints calls|none]
+                   [--stats none|counts|timing] [-o <file>]";
+
+enum GeneratorKind {
+    GlobalTyped,
+    DebugStruct,
+}
+
+struct Config {
+    api:          Api,
+    version:      (u8, u8),
+    profile:      Option<Profile>,
+    fallbacks:    Fallbacks,
+    extensions:   Vec<String>,
+    debug_prints: DebugPrints,
+    call_stats:   CallStats,
+    generator:    GeneratorKind,
+    output:       Option<String>,
+}
+
+fn parse_args() -> Result<Config, String> {
+    let mut api = None;
+    let mut version = None;
+    let mut config = Config {
+        api:          Api::Gl,
+        version:      (0, 0),
+        profile:      None,
+        fallbacks:    Fallbacks::All,
+        extensions:   Vec::new(),
+        debug_prints: DebugPrints::None,
+        call_stats:   CallStats::None,
+        generator:    GeneratorKind::GlobalTyped,
+        output:       None,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match &*arg {
+            "--api" => api = Some(args::value(&mut args, &arg)?.parse()?),
+            "--version" => version = Some(args::parse_version(&args::value(&mut args, &arg)?)?),
+            "--profile" => config.profile = Some(args::value(&mut args, &arg)?.parse()?),
+            "--extensions" => config.extensions.extend(args::parse_list(&args::value(&mut args, &arg)?)),
+            "--fallbacks" => {
+                config.fallbacks = match &*args::value(&mut args, &arg)? {
+                    "all" => Fallbacks::All,
+                    "none" => Fallbacks::None,
+                    other => return Err(format!("unknown fallbacks `{}`", other)),
+                }
+            },
+            "--generator" => {
+                config.generator = match &*args::value(&mut args, &arg)? {
+                    "global-typed" => GeneratorKind::GlobalTyped,
+                    "debug-struct" => GeneratorKind::DebugStruct,
+                    other => return Err(format!("unknown generator `{}`", other)),
+                }
+            },
+            "--debug-prints" => {
+                config.debug_prints = match &*args::value(&mut args, &arg)? {
+                    "calls" => DebugPrints::FunctionCalls,
+                    "none" => DebugPrints::None,
+                    other => return Err(format!("unknown debug prints `{}`", other)),
+                }
+            },
+            "--stats" => {
+                config.call_stats = match &*args::value(&mut args, &arg)? {
+                    "none" => CallStats::None,
+                    "counts" => CallStats::Counts,
+                    "timing" => CallStats::Timing,
+                    other => return Err(format!("unknown stats `{}`", other)),
+                }
+            },
+            "-o" | "--output" => config.output = Some(args::value(&mut args, &arg)?),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            other => return Err(format!("unexpected argument `{}`\n{}", other, USAGE)),
+        }
+    }
+
+    match (api, version) {
+        (Some(api), Some(version)) => {
+            config.api = api;
+            config.version = version;
+            Ok(config)
+        },
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn write_bindings<W>(config: &Config, dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    let mut builder = RegistryBuilder::new(config.api, config.version)
+        .fallbacks(config.fallbacks)
+        .extensions(&config.extensions)
+        .debug_prints(config.debug_prints)
+        .call_stats(config.call_stats);
+    if let Some(profile) = config.profile {
+        builder = builder.profile(profile);
+    }
+    let registry = builder.build().map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+    match config.generator {
+        GeneratorKind::GlobalTyped => registry.write_bindings(GlobalTypedGenerator, dest)?,
+        GeneratorKind::DebugStruct => registry.write_bindings(DebugStructGenerator, dest)?,
+    }
+    dest.flush()
+}
+
+fn main() {
+    let config = parse_args().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(2);
+    });
+
+    let written = match config.output {
+        Some(ref path) => File::create(path).and_then(|file| write_bindings(&config, &mut BufWriter::new(file))),
+        None => write_bindings(&config, &mut io::stdout().lock()),
+    };
+    if let Err(err) = written {
+        eprintln!("Could not write bindings: {}", err);
+        process::exit(1);
+    }
+}
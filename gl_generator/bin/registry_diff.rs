@@ -0,0 +1,113 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+//! Prints the commands and enums which differ between two registry configurations.
+//!
+//! ```text
+//! gl_registry_diff gl:4.6:core gl:4.1:core
+//! gl_registry_diff gl:4.3:core gles2:3.2 --to-extensions GL_EXT_texture_buffer
+//! ```
+
+extern crate gl_generator;
+
+use gl_generator::{Api, Fallbacks, Profile, Registry, RegistryBuilder, RegistryError};
+use std::{env, process};
+
+use args::parse_version;
+
+mod args;
+
+const USAGE: &str = "usage: gl_registry_diff <api>:<version>[:<profile>] <api>:<version>[:<profile>] \
+                     [--from-extensions <ext>,...] [--to-extensions <ext>,...]";
+
+struct Config {
+    api:        Api,
+    version:    (u8, u8),
+    profile:    Option<Profile>,
+    extensions: Vec<String>,
+}
+
+impl Config {
+    fn parse(spec: &str) -> Result<Config, String> {
+        let mut parts = spec.split(':');
+        let api = parts.next().unwrap_or_default().parse()?;
+        let version = match parts.next() {
+            Some(version) => parse_version(version)?,
+            None => return Err(format!("missing version in `{}`", spec)),
+        };
+        let profile = match parts.next() {
+            Some(profile) => Some(profile.parse()?),
+            None => None,
+        };
+        if parts.next().is_some() {
+            return Err(format!("unexpected trailing `:` in `{}`", spec));
+        }
+
+        Ok(Config {
+            api,
+            version,
+            profile,
+            extensions: Vec::new(),
+        })
+    }
+
+    fn registry(&self) -> Result<Registry, RegistryError> {
+        let mut builder = RegistryBuilder::new(self.api, self.version).fallbacks(Fallbacks::None).extensions(&self.extensions);
+        if let Some(profile) = self.profile {
+            builder = builder.profile(profile);
+        }
+        builder.build()
+    }
+}
+
+fn parse_args() -> Result<(Config, Config), String> {
+    let mut configs = Vec::new();
+    let mut from_extensions = Vec::new();
+    let mut to_extensions = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let extensions = match &*arg {
+            "--from-extensions" => &mut from_extensions,
+            "--to-extensions" => &mut to_extensions,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => {
+                configs.push(Config::parse(&arg)?);
+                continue;
+            },
+        };
+        extensions.extend(args::parse_list(&args::value(&mut args, &arg)?));
+    }
+
+    if configs.len() != 2 {
+        return Err(USAGE.to_string());
+    }
+    let mut to = configs.pop().unwrap();
+    let mut from = configs.pop().unwrap();
+    from.extensions = from_extensions;
+    to.extensions = to_extensions;
+    Ok((from, to))
+}
+
+fn main() {
+    let (from, to) = parse_args().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(2);
+    });
+
+    let registries = from.registry().and_then(|from| Ok((from, to.registry()?)));
+    let (from, to) = registries.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+
+    let diff = from.diff(&to);
+    if diff.is_empty() {
+        println!("No differences");
+    } else {
+        print!("{}", diff);
+    }
+}
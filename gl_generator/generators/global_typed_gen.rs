@@ -6,8 +6,10 @@
 
 use std::{borrow::Cow, io};
 
+use super::{CallStats, CommandOverride, GeneratorOptions, TypeConversion};
 use lazy_static::*;
-use registry::{Cmd, DebugPrints, Registry};
+use registry::{Binding, Cmd, DebugPrints, Registry, TypeKind, CALLED_CMDS};
+use Api;
 
 #[allow(missing_copy_implementations)]
 pub struct GlobalTypedGenerator;
@@ -15,14 +17,51 @@ pub struct GlobalTypedGenerator;
 impl super::Generator for GlobalTypedGenerator {
     fn write<W>(&self, registry: &Registry, dest: &mut W) -> io::Result<()>
     where W: io::Write {
+        let mut registry = Cow::Borrowed(registry);
+        if registry.options.object_handles {
+            registry = Cow::Owned(with_object_handles(&registry));
+        }
+        if registry.api == Api::Egl {
+            registry = Cow::Owned(with_egl_handles(&registry));
+        }
+        let (conversions, return_conversions) = api_conversions(registry.api);
+        if !conversions.is_empty() || !return_conversions.is_empty() {
+            // after the user's, which take precedence
+            let options = &mut registry.to_mut().options;
+            options.conversions.extend(conversions);
+            options.return_conversions.extend(return_conversions);
+        }
+        let mapping_fns = mapping_fns(&registry);
+        let replacements = [&mapping_fns[..], &egl_config_fns(&registry)[..]].concat();
+        if !replacements.is_empty() {
+            let overrides = &mut registry.to_mut().options.overrides;
+            for (cmd, src) in replacements {
+                overrides.insert(cmd.to_string(), CommandOverride::Replace(src));
+            }
+        }
+        let registry = &*registry;
+
         write_header(dest)?;
         write_metaloadfn(dest)?;
         write_type_aliases(registry, dest)?;
+        write_attrib_list(registry, dest)?;
+        if !mapping_fns.is_empty() {
+            writeln!(dest, "{}", include_str!("templates/mapped_buffer.rs"))?;
+        }
+        if !fence_cmds(registry).is_empty() {
+            writeln!(dest, "{}", include_str!("templates/fence.rs"))?;
+        }
         write_enums(registry, dest)?;
         write_fns(registry, dest)?;
+        write_raw_fns(registry, dest)?;
+        write_array_fns(registry, dest)?;
+        write_slice_fns(registry, dest)?;
+        write_command_buffer(registry, dest)?;
         write_fnptr_struct_def(dest)?;
         write_ptrs(registry, dest)?;
-        write_fn_mods(registry, dest)?;
+        write_stats(registry, dest)?;
+        write_shadow_state(registry, dest)?;
+        write_objects(registry, dest)?;
         write_panicking_fns(registry, dest)?;
         write_load_fn(registry, dest)?;
         Ok(())
@@ -31,12 +70,13 @@ impl super::Generator for GlobalTypedGenerator {
 
 /// Creates a `__gl_imports` module which contains all the external symbols that we need for the
 ///  bindings.
+///
+/// The bindings have no inner attributes at the root so that they can be `include!`d.
 fn write_header<W>(dest: &mut W) -> io::Result<()>
 where W: io::Write {
     writeln!(
         dest,
         r#"
-#![allow(unused_parens, non_snake_case, dead_code, non_upper_case_globals, unused_variables, dead_code)]
 mod __gl_imports {{
     pub use std::mem;
     pub use std::os::raw;
@@ -80,14 +120,430 @@ pub mod types {{
     "#
     )?;
 
-    super::gen_types(registry.api, dest)?;
+    super::gen_types(registry, dest)?;
+    if registry.options.object_handles {
+        write_handle_types(dest)?;
+    }
+    if registry.api == Api::Egl {
+        write_egl_handle_types(dest)?;
+    }
 
     writeln!(
         dest,
         "
 }}
     "
-    )
+    )?;
+
+    if registry.options.object_handles {
+        writeln!(dest, "pub use self::types::{{{}}};", HANDLES.join(", "))?;
+    }
+    Ok(())
+}
+
+/// The object name newtypes created with `GeneratorOptions::object_handles`.
+const HANDLES: &[&str] = &["Buffer", "Texture", "Program", "Shader", "VertexArray", "Framebuffer", "Renderbuffer", "Sampler", "Query"];
+
+/// Creates the object name newtypes in the `types` module.
+///
+/// They are `repr(transparent)`, so the function pointers are called with them in place of `GLuint`.
+fn write_handle_types<W>(dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    for handle in HANDLES {
+        writeln!(
+            dest,
+            "
+    /// A {handle} object name. `{handle}(0)` is the default object, or none.
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct {handle}(pub GLuint);",
+            handle = handle
+        )?;
+    }
+    Ok(())
+}
+
+/// Returns `registry` with the object names among the command parameters and return types
+///  replaced by the newtypes of `HANDLES`.
+fn with_object_handles(registry: &Registry) -> Registry {
+    let mut registry = registry.clone();
+    registry.cmds = registry.cmds.into_iter().map(|mut cmd| {
+        let ident = cmd.proto.ident.clone();
+        if let Some(handle) = returned_handle(&ident) {
+            if cmd.proto.ty == "types::GLuint" {
+                cmd.proto.ty = Cow::Owned(format!("types::{}", handle));
+            }
+        }
+        for param in &mut cmd.params {
+            let handle = match handle_of(&ident, param) {
+                Some(handle) => handle,
+                None => continue,
+            };
+            let ty = match &*param.ty {
+                "types::GLuint" => format!("types::{}", handle),
+                "*const types::GLuint" => format!("*const types::{}", handle),
+                "*mut types::GLuint" => format!("*mut types::{}", handle),
+                _ => continue,
+            };
+            param.ty = Cow::Owned(ty);
+        }
+        cmd
+    }).collect();
+    registry
+}
+
+/// The newtype of the object name returned by the command `cmd`, e.g. `Shader` for `CreateShader`.
+fn returned_handle(cmd: &str) -> Option<&'static str> {
+    match cmd {
+        "CreateShader" => Some("Shader"),
+        "CreateProgram" | "CreateShaderProgramv" => Some("Program"),
+        _ => None,
+    }
+}
+
+/// The newtype of the object name `param` of the command `cmd`, inferred from the parameter's
+///  group and name.
+fn handle_of(cmd: &str, param: &Binding) -> Option<&'static str> {
+    match param.group.as_deref() {
+        Some("Texture") => return Some("Texture"),
+        Some("Framebuffer") => return Some("Framebuffer"),
+        Some("Renderbuffer") => return Some("Renderbuffer"),
+        _ => (),
+    }
+
+    match &*param.ident {
+        "buffer" | "buffers" | "readBuffer" | "writeBuffer" => Some("Buffer"),
+        "texture" | "textures" | "origtexture" => Some("Texture"),
+        "program" | "programs" => Some("Program"),
+        "shader" | "shaders" => Some("Shader"),
+        "vaobj" => Some("VertexArray"),
+        "array" | "arrays" if cmd.contains("VertexArray") => Some("VertexArray"),
+        "framebuffer" | "framebuffers" | "readFramebuffer" | "drawFramebuffer" => Some("Framebuffer"),
+        "renderbuffer" | "renderbuffers" => Some("Renderbuffer"),
+        "sampler" | "samplers" => Some("Sampler"),
+        "id" | "ids" if cmd.contains("Quer") => Some("Query"),
+        _ => None,
+    }
+}
+
+/// The EGL object handles, which are newtypes around the `void *` of the registry so that they
+///  can't be mixed up, e.g. a surface passed as a context.
+const EGL_HANDLES: &[&str] = &[
+    "EGLConfig",
+    "EGLContext",
+    "EGLDeviceEXT",
+    "EGLDisplay",
+    "EGLImage",
+    "EGLImageKHR",
+    "EGLOutputLayerEXT",
+    "EGLOutputPortEXT",
+    "EGLStreamKHR",
+    "EGLSurface",
+    "EGLSync",
+    "EGLSyncKHR",
+    "EGLSyncNV",
+];
+
+/// Creates the EGL handle newtypes in the `types` module.
+///
+/// They are `repr(transparent)` like the object name newtypes, and the default handle is null.
+fn write_egl_handle_types<W>(dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    for handle in EGL_HANDLES {
+        writeln!(
+            dest,
+            "
+    /// An `{handle}` handle.
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct {handle}(pub *mut __gl_imports::raw::c_void);
+
+    impl Default for {handle} {{
+        fn default() -> Self {{
+            {handle}(0 as *mut _)
+        }}
+    }}",
+            handle = handle
+        )?;
+    }
+    Ok(())
+}
+
+/// Returns the EGL `registry` with the types of `EGL_HANDLES` left to `write_egl_handle_types`, and
+///  the null handles like `NO_DISPLAY` constructing the newtypes.
+fn with_egl_handles(registry: &Registry) -> Registry {
+    let mut registry = registry.clone();
+    for ty in registry.types.iter_mut().filter(|ty| EGL_HANDLES.contains(&&*ty.ident)) {
+        ty.kind = TypeKind::External;
+    }
+    registry.enums = registry.enums.into_iter().map(|mut enm| {
+        if EGL_HANDLES.contains(&&*enm.ty) {
+            enm.value = format!("types::{}({} as *mut __gl_imports::raw::c_void)", enm.ty, enm.value);
+            enm.cast = false;
+        }
+        enm
+    }).collect();
+    registry
+}
+
+/// Returns the replacements of `eglChooseConfig` and `eglGetConfigs`, which take the size of
+///  `configs` from the slice. Commands in `GeneratorOptions::overrides` keep the user's override.
+fn egl_config_fns(registry: &Registry) -> Vec<(&'static str, String)> {
+    if registry.api != Api::Egl {
+        return Vec::new();
+    }
+    let call = |cmd: &str, args: &str| {
+        format!(
+            "let config_size = configs.len().min(types::EGLint::MAX as usize) as types::EGLint;
+// NULL queries the number of configs
+let configs = if configs.is_empty() {{ 0 as *mut _ }} else {{ configs.as_mut_ptr() }};
+let mut num_config = 0;
+let func_retv = unsafe {{ raw::{cmd}({args}) }};
+(func_retv, num_config)",
+            cmd = cmd,
+            args = args
+        )
+    };
+
+    let mut fns = vec![
+        (
+            "ChooseConfig",
+            format!(
+                "/// Writes the configs of `dpy` matching `attrib_list` to `configs`, returning their number. If
+///  `configs` is empty, the number of all matching configs is returned.
+#[inline]
+pub fn ChooseConfig(dpy: types::EGLDisplay, attrib_list: &AttribList<types::EGLint>, configs: &mut [types::EGLConfig]) -> (types::EGLBoolean, types::EGLint) {{
+{}
+}}",
+                call("ChooseConfig", "dpy, attrib_list.as_ptr(), configs, config_size, &mut num_config")
+            ),
+        ),
+        (
+            "GetConfigs",
+            format!(
+                "/// Writes the configs of `dpy` to `configs`, returning their number. If `configs` is empty, the
+///  number of all configs is returned.
+#[inline]
+pub fn GetConfigs(dpy: types::EGLDisplay, configs: &mut [types::EGLConfig]) -> (types::EGLBoolean, types::EGLint) {{
+{}
+}}",
+                call("GetConfigs", "dpy, configs, config_size, &mut num_config")
+            ),
+        ),
+    ];
+    fns.retain(|(cmd, _)| registry.cmds.iter().any(|c| c.proto.ident == *cmd) && !registry.options.overrides.contains_key(*cmd));
+    fns
+}
+
+/// Creates the `AttribList` builder for the attribute lists of EGL, GLX and WGL.
+fn write_attrib_list<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    let impls = match registry.api {
+        Api::Egl => {
+            "impl Attrib for types::EGLint {
+    const NONE: Self = NONE as types::EGLint;
+
+    fn from_i64(value: i64) -> Self {
+        value as Self
+    }
+}
+
+impl Attrib for types::EGLAttrib {
+    const NONE: Self = NONE as types::EGLAttrib;
+
+    fn from_i64(value: i64) -> Self {
+        value as Self
+    }
+}"
+        },
+        Api::Glx | Api::Wgl => {
+            "impl Attrib for __gl_imports::raw::c_int {
+    const NONE: Self = 0;
+
+    fn from_i64(value: i64) -> Self {
+        value as Self
+    }
+}"
+        },
+        _ => return Ok(()),
+    };
+
+    writeln!(dest, "{}", include_str!("templates/attrib_list.rs"))?;
+    writeln!(dest, "{}", impls)
+}
+
+/// Returns the built-in parameter and return conversions of EGL, GLX and WGL, which are applied
+///  after `GeneratorOptions::conversions` and `GeneratorOptions::return_conversions`.
+///
+/// Attribute lists take an `AttribList`, procedure names a `&str`, and the single values written
+///  to pointers, like the version of `eglInitialize`, are returned.
+fn api_conversions(api: Api) -> (Vec<TypeConversion>, Vec<TypeConversion>) {
+    const AS_PTR: &str = "let {param} = {param}.as_ptr() as *const _;";
+    const AS_MUT_PTR: &str = "let {param} = {param}.as_ptr() as *mut _;";
+    const C_STRING: &str = r#"let {param}_c_string = std::ffi::CString::new({param}.as_bytes()).expect("Failed to create CString in GL Call {cmd}");
+let {param} = {param}_c_string.as_ptr() as *const _;"#;
+    const RETURN: &str = "let mut {param}_ret = 0;\nlet {param} = &mut {param}_ret;";
+
+    let returns = |ty: &str, rust_ty: &str, params: &[&str]| -> Vec<TypeConversion> {
+        params.iter().map(|param| TypeConversion::new(ty, rust_ty, RETURN).param(*param)).collect()
+    };
+
+    match api {
+        Api::Egl => (
+            vec![
+                TypeConversion::new("*const types::EGLint", "&AttribList<types::EGLint>", AS_PTR).param("attrib_list"),
+                TypeConversion::new("*const types::EGLAttrib", "&AttribList<types::EGLAttrib>", AS_PTR).param("attrib_list"),
+                TypeConversion::new("*const __gl_imports::raw::c_char", "&str", C_STRING).param("procname"),
+            ],
+            [
+                returns("*mut types::EGLint", "types::EGLint", &["value", "major", "minor", "num_config"]),
+                returns("*mut types::EGLAttrib", "types::EGLAttrib", &["value"]),
+            ]
+            .concat(),
+        ),
+        Api::Glx => (
+            vec![
+                TypeConversion::new("*const __gl_imports::raw::c_int", "&AttribList<__gl_imports::raw::c_int>", AS_PTR).param("attrib_list"),
+                TypeConversion::new("*const __gl_imports::raw::c_int", "&AttribList<__gl_imports::raw::c_int>", AS_PTR).param("attribList"),
+                TypeConversion::new("*mut __gl_imports::raw::c_int", "&AttribList<__gl_imports::raw::c_int>", AS_MUT_PTR).param("attribList"),
+                TypeConversion::new("*const types::GLubyte", "&str", C_STRING).param("procName"),
+            ],
+            returns("*mut __gl_imports::raw::c_int", "__gl_imports::raw::c_int", &["value", "nelements", "maj", "min", "errorb", "event"]),
+        ),
+        Api::Wgl => (
+            vec![
+                TypeConversion::new("*const __gl_imports::raw::c_int", "&AttribList<__gl_imports::raw::c_int>", AS_PTR).param("attribList"),
+                TypeConversion::new("*const __gl_imports::raw::c_int", "&AttribList<__gl_imports::raw::c_int>", AS_PTR).param("piAttribIList"),
+            ],
+            Vec::new(),
+        ),
+        _ => (Vec::new(), Vec::new()),
+    }
+}
+
+/// Returns the replacements of the buffer mapping commands, which return a `MappedBuffer` instead
+///  of a pointer, for the commands whose unmapping command is in the registry too, and for
+///  `MapBuffer` also `GetBufferParameteri64v` querying the size. Commands in
+///  `GeneratorOptions::overrides` keep the user's override.
+///
+/// The pointer is still returned by `raw::<name>`, as the replacements are overrides.
+fn mapping_fns(registry: &Registry) -> Vec<(&'static str, String)> {
+    let id = |ident: &str| registry.cmds.iter().position(|cmd| cmd.proto.ident == ident);
+    let unmap_fn = |id: usize| {
+        let call = format!("__gl_imports::mem::transmute::<_, extern \"system\" fn(types::GLuint) -> types::GLboolean>(storage::FNS[{}].f)(name)", id);
+        format!("fn unmap(name: types::GLuint) -> types::GLboolean {{\n    unsafe {{ {} }}\n}}", recorded(&registry.options, id, &call))
+    };
+    let mut fns = Vec::new();
+
+    if let (Some(_), Some(unmap), Some(get)) = (id("MapBuffer"), id("UnmapBuffer"), id("GetBufferParameteri64v")) {
+        fns.push((
+            "MapBuffer",
+            format!(
+                "/// Maps the buffer bound to `target` as elements of `T` with the `access` policy.
+///  `raw::MapBuffer` returns the pointer instead.
+///
+/// Returns `None` if the buffer could not be mapped. It is unmapped with `UnmapBuffer` when the
+///  `MappedBuffer` is dropped.
+///
+/// # Safety
+///
+/// - Every bit pattern must be a valid `T`.
+/// - The buffer must stay bound to `target` until the `MappedBuffer` is dropped.
+/// - The buffer must not be unmapped otherwise or deleted while it is mapped.
+#[inline]
+pub unsafe fn MapBuffer<T: Copy>(target: types::GLenum, access: types::GLenum) -> Option<MappedBuffer<T>> {{
+{unmap_fn}
+let mut size: types::GLint64 = 0;
+// BUFFER_SIZE
+{get_size};
+let ptr = raw::MapBuffer(target, access);
+// READ_ONLY, WRITE_ONLY and READ_WRITE
+MappedBuffer::new(ptr, size as usize, access != 0x88B9, access != 0x88B8, target, unmap)
+}}",
+                unmap_fn = unmap_fn(unmap),
+                get_size = recorded(
+                    &registry.options,
+                    get,
+                    &format!(
+                        "__gl_imports::mem::transmute::<_, extern \"system\" fn(types::GLenum, types::GLenum, *mut types::GLint64)>(storage::FNS[{}].f)(target, 0x8764, &mut size)",
+                        get
+                    )
+                ),
+            ),
+        ));
+    }
+
+    if let (Some(_), Some(unmap)) = (id("MapBufferRange"), id("UnmapBuffer")) {
+        fns.push((
+            "MapBufferRange",
+            format!(
+                "/// Maps `length` bytes from `offset` of the buffer bound to `target` as elements of `T` with the
+///  `access` bits. `raw::MapBufferRange` returns the pointer instead.
+///
+/// Returns `None` if the range could not be mapped. The buffer is unmapped with `UnmapBuffer` when
+///  the `MappedBuffer` is dropped.
+///
+/// # Safety
+///
+/// - Every bit pattern must be a valid `T`.
+/// - The buffer must stay bound to `target` until the `MappedBuffer` is dropped.
+/// - The buffer must not be unmapped otherwise or deleted while it is mapped, and the range must
+///   not be written by commands while it is mapped with `MAP_PERSISTENT_BIT`.
+#[inline]
+pub unsafe fn MapBufferRange<T: Copy>(target: types::GLenum, offset: types::GLintptr, length: types::GLsizeiptr, access: types::GLbitfield) -> Option<MappedBuffer<T>> {{
+{unmap_fn}
+let ptr = raw::MapBufferRange(target, offset, length, access);
+// MAP_READ_BIT and MAP_WRITE_BIT
+MappedBuffer::new(ptr, length as usize, access & 0x1 != 0, access & 0x2 != 0, target, unmap)
+}}",
+                unmap_fn = unmap_fn(unmap),
+            ),
+        ));
+    }
+
+    let map_named = registry.cmds.iter().find(|cmd| cmd.proto.ident == "MapNamedBufferRange");
+    if let (Some(map), Some(unmap)) = (map_named, id("UnmapNamedBuffer")) {
+        let buffer_ty = &map.params[0].ty;
+        fns.push((
+            "MapNamedBufferRange",
+            format!(
+                "/// Maps `length` bytes from `offset` of `buffer` as elements of `T` with the `access` bits.
+///  `raw::MapNamedBufferRange` returns the pointer instead.
+///
+/// Returns `None` if the range could not be mapped. The buffer is unmapped with `UnmapNamedBuffer`
+///  when the `MappedBuffer` is dropped.
+///
+/// # Safety
+///
+/// - Every bit pattern must be a valid `T`.
+/// - The buffer must not be unmapped otherwise or deleted while it is mapped, and the range must
+///   not be written by commands while it is mapped with `MAP_PERSISTENT_BIT`.
+#[inline]
+pub unsafe fn MapNamedBufferRange<T: Copy>(buffer: {buffer_ty}, offset: types::GLintptr, length: types::GLsizeiptr, access: types::GLbitfield) -> Option<MappedBuffer<T>> {{
+{unmap_fn}
+let ptr = raw::MapNamedBufferRange(buffer, offset, length, access);
+// MAP_READ_BIT and MAP_WRITE_BIT
+MappedBuffer::new(ptr, length as usize, access & 0x1 != 0, access & 0x2 != 0, {name}, unmap)
+}}",
+                unmap_fn = unmap_fn(unmap),
+                buffer_ty = buffer_ty,
+                name = if buffer_ty == "types::GLuint" { "buffer" } else { "buffer.0" },
+            ),
+        ));
+    }
+
+    fns.retain(|(cmd, _)| !registry.options.overrides.contains_key(*cmd));
+    fns
+}
+
+/// Returns the commands called by the `Fence` type, which is created if the registry has all of
+///  them, or else nothing.
+fn fence_cmds(registry: &Registry) -> &'static [&'static str] {
+    let (_, cmds) = CALLED_CMDS.iter().find(|(ident, _)| *ident == "Fence").unwrap();
+    match cmds.iter().all(|ident| registry.cmds.iter().any(|cmd| cmd.proto.ident == *ident)) {
+        true => cmds,
+        false => &[],
+    }
 }
 
 /// Creates all the `<enum>` elements at the root of the bindings.
@@ -100,84 +556,364 @@ where W: io::Write {
     Ok(())
 }
 
-/// Creates the functions corresponding to the GL commands.
+/// Creates the functions corresponding to the GL commands, in one module per feature or extension
+///  which is glob re-exported at the root of the bindings, see `cmd_modules`.
+fn write_fns<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    for (name, cmds) in cmd_modules(registry) {
+        writeln!(
+            dest,
+            "/// The commands introduced by `{name}`.
+pub mod {module} {{
+    #![allow(unused_parens, non_snake_case, unused_variables, unused_unsafe)]
+    use super::*;",
+            name = name,
+            module = name.to_lowercase()
+        )?;
+        for (id, cmd) in cmds {
+            write_fn(registry, id, cmd, dest)?;
+        }
+        writeln!(dest, "}}\npub use self::{}::*;", name.to_lowercase())?;
+    }
+
+    Ok(())
+}
+
+/// Groups the commands with their ids by the first feature requiring them, or else by the first
+///  extension, and returns the groups in registry order. Other commands are grouped in `other`.
+fn cmd_modules(registry: &Registry) -> Vec<(&str, Vec<(usize, &Cmd)>)> {
+    let names = registry.features.iter().map(|f| &*f.name).chain(registry.extensions.iter().map(|e| &*e.name)).chain(Some("other"));
+    let mut modules = names.map(|name| (name, Vec::new())).collect::<Vec<_>>();
+
+    for (id, cmd) in registry.cmds.iter().enumerate() {
+        let ident = &*cmd.proto.ident;
+        let name = match registry.introduced_in(ident) {
+            Some(feature) => &*feature.name,
+            None => registry.extensions_providing(ident).first().map_or("other", |e| &*e.name),
+        };
+        if let Some((_, cmds)) = modules.iter_mut().find(|(n, _)| *n == name) {
+            cmds.push((id, cmd));
+        }
+    }
+
+    modules.retain(|(_, cmds)| !cmds.is_empty());
+    modules
+}
+
+/// Creates the function for the command `cmd` with the id `id`.
 ///
 /// The function calls the corresponding function pointer stored in the `storage` module created
-///  by `write_ptrs`.
-fn write_fns<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
+///  by `write_ptrs`. Commands in `GeneratorOptions::overrides` get the given source or nothing.
+///  Commands which a feature up to the requested version removes from the core profile are
+///  `#[deprecated]`. Commands with `safety_requirements` are `unsafe` and document them.
+fn write_fn<W>(registry: &Registry, id: usize, cmd: &Cmd, dest: &mut W) -> io::Result<()>
 where W: io::Write {
-    for cmd in &registry.cmds {
+    let options = &registry.options;
+    {
+        match options.overrides.get(&cmd.proto.ident) {
+            Some(CommandOverride::Suppress) => return Ok(()),
+            Some(CommandOverride::Replace(src)) => return writeln!(dest, "{}", src),
+            None => (),
+        }
+
         if let Some(v) = registry.aliases.get(&cmd.proto.ident) {
             writeln!(dest, "            /// Fallbacks: {}", v.join(", "))?;
         }
+        let requirements = safety_requirements(options, cmd);
+        if !requirements.is_empty() {
+            if registry.aliases.contains_key(&cmd.proto.ident) {
+                writeln!(dest, "///")?;
+            }
+            writeln!(dest, "/// # Safety\n///")?;
+            for (_, requirement) in &requirements {
+                writeln!(dest, "/// - {}.", requirement)?;
+            }
+            if slice_params(options, cmd).is_some() {
+                writeln!(dest, "///\n/// `slices::{}` takes the lengths from slices instead.", cmd.proto.ident)?;
+            }
+        }
+        // only compatibility profile registries still contain removed commands
+        if let Some(feature) = registry.removed_in_core(&cmd.proto.ident) {
+            writeln!(dest, "#[deprecated(note = \"removed in core {}\")]", feature.number)?;
+        }
 
+        let (debug_string_initializer, debug_string_print) = debug_call(registry, cmd);
         writeln!(
             dest,
             "#[inline]
-pub fn {name}({params}) -> {return_suffix} {{
+pub {unsafe_}fn {name}({params}) -> {return_suffix} {{
 unsafe {{\
 {conversions}\
 {initializers}\
 {debug_string_initializer}
-let func_retv = __gl_imports::mem::transmute::<_, extern \"system\" fn({typed_params}) -> {return_suffix_without_ret_vals}>(storage::{name}.f)({idents});\
+let func_retv = {call};\
 {debug_string_print}
 {ret}
 }}
 }}",
+            unsafe_ = if requirements.is_empty() { "" } else { "unsafe " },
             name = cmd.proto.ident,
-            params = super::gen_parameters(&get_rustified(&without_return_types_params(cmd)), true, true).join(", "),
-            typed_params = super::gen_parameters(cmd, false, true).join(", "),
-            return_suffix = add_return_types(cmd, (&*cmd.proto.ty).to_string()),
-            return_suffix_without_ret_vals = &*cmd.proto.ty,
-            idents = super::gen_parameters(cmd, true, false).join(", "),
-            debug_string_initializer = match registry.debug_prints {
-                DebugPrints::None => "".to_owned(),
-                DebugPrints::FunctionCalls => {
-                    let params = super::gen_parameters(cmd, true, false);
-                    format!(
-                        "\nlet debug_string = format!(\"gl{name}{para1}{{:?}}{para2}\", ({debug_idents}));",
-                        name = cmd.proto.ident,
-                        debug_idents = {
-                            if params.len() != 0 {
-                                if params.len() < 15 {
-                                    let joined = params.join(", &");
-                                    if !joined.contains("callback") {
-                                        format!("&{}", joined)
-                                    } else {
-                                        "\"<callback function as parameter>\"".to_owned()
-                                    }
-                                } else {
-                                    // because there is no debug impl for a tuple of size 16 and up
-                                    "\"<too many arguments to display>\"".to_owned()
-                                }
+            params = super::gen_parameters(&get_rustified(options, &without_return_types_params(options, cmd)), true, true).join(", "),
+            return_suffix = add_return_types(options, cmd, cmd.proto.ty.to_string()),
+            call = ffi_call(options, id, cmd),
+            debug_string_initializer = debug_string_initializer,
+            debug_string_print = debug_string_print,
+            conversions = get_conversions(options, cmd),
+            initializers = get_initializers(options, cmd),
+            ret = get_return_args(options, cmd),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Returns the expression calling the function pointer of the command `cmd` with the id `id` with
+///  its C arguments, which is skipped if it wouldn't change the state in the `shadow` module.
+fn ffi_call(options: &GeneratorOptions, id: usize, cmd: &Cmd) -> String {
+    let idents = super::gen_parameters(cmd, true, false).join(", ");
+    let call = format!(
+        "__gl_imports::mem::transmute::<_, extern \"system\" fn({typed_params}) -> {return_suffix}>(storage::FNS[{id}].f)({idents})",
+        id = id,
+        typed_params = super::gen_parameters(cmd, false, true).join(", "),
+        return_suffix = &*cmd.proto.ty,
+        idents = idents,
+    );
+    let call = tracked(options, cmd, &recorded(options, id, &call));
+    if !options.shadow_state {
+        return call;
+    }
+
+    let ident = &*cmd.proto.ident;
+    if SHADOW_SETTERS.iter().any(|(cmd, _, _)| *cmd == ident) {
+        format!("if super::shadow::{}({}) {{ {} }} else {{ super::shadow::skip({}) }}", ident, idents, call, id)
+    } else if SHADOW_INVALIDATORS.iter().any(|(cmd, _)| *cmd == ident) {
+        format!("{{ super::shadow::{}(); {} }}", ident, call)
+    } else {
+        call
+    }
+}
+
+/// Wraps the expression `call` calling the function pointer of `cmd` so that the objects it creates
+///  or deletes are tracked in the `objects` module, if `GeneratorOptions::track_objects` is set.
+fn tracked(options: &GeneratorOptions, cmd: &Cmd, call: &str) -> String {
+    let ident = &*cmd.proto.ident;
+    let kind = TRACKED_OBJECTS.iter().find(|(_, creators, deleters)| creators.contains(&ident) || deleters.contains(&ident));
+    let kind = match kind {
+        Some((kind, _, _)) if options.track_objects => kind,
+        _ => return call.to_string(),
+    };
+
+    // either a single name or an array of names, which are `GLuint`s or the `object_handles` type,
+    //  whose `len` is a `GLsizei` parameter, e.g. `n`
+    let name_tys = ["types::GLuint".to_string(), format!("types::{}", kind)];
+    let is_names = |param: &Binding| param.ty.strip_prefix("*mut ").or_else(|| param.ty.strip_prefix("*const ")).is_some_and(|ty| name_tys.iter().any(|name_ty| name_ty == ty));
+    let names = cmd.params.iter().filter(|param| is_names(param)).find_map(|names| {
+        let n = cmd.params.iter().find(|n| names.len.as_ref() == Some(&n.ident) && n.ty == "types::GLsizei")?;
+        Some((&*n.ident, &*names.ident))
+    });
+    if ident.starts_with("Delete") {
+        match names {
+            Some((n, names)) => format!("{{ super::objects::deleted(\"{}\", {}, {} as *const _); {} }}", kind, n, names, call),
+            None => format!("{{ super::objects::deleted_one(\"{}\", {}); {} }}", kind, cmd.params[0].ident, call),
+        }
+    } else {
+        match names {
+            Some((n, names)) => format!("{{ let objects_ret = {}; super::objects::created(\"{}\", {}, {} as *const _); objects_ret }}", call, kind, n, names),
+            None => format!("{{ let objects_ret = {}; super::objects::created_one(\"{}\", objects_ret); objects_ret }}", call, kind),
+        }
+    }
+}
+
+/// Wraps the expression `call` calling the function pointer with the id `id` so that the call is
+///  recorded in the `stats` module, depending on `GeneratorOptions::call_stats`.
+fn recorded(options: &GeneratorOptions, id: usize, call: &str) -> String {
+    match options.call_stats {
+        CallStats::None => call.to_string(),
+        CallStats::Counts => format!("{{ super::stats::record({}, None); {} }}", id, call),
+        CallStats::Timing => format!(
+            "{{ let stats_start = std::time::Instant::now(); let stats_ret = {call}; super::stats::record({id}, Some(stats_start)); stats_ret }}",
+            call = call,
+            id = id
+        ),
+    }
+}
+
+/// Returns the code formatting the call of `cmd` with its C arguments before the call, and the
+///  code printing it with the returned values after it, for `DebugPrints::FunctionCalls`.
+fn debug_call(registry: &Registry, cmd: &Cmd) -> (String, String) {
+    let options = &registry.options;
+    let initializer = match options.debug_prints {
+        DebugPrints::None => "".to_owned(),
+        DebugPrints::FunctionCalls => {
+            let params = super::gen_parameters(cmd, true, false);
+            format!(
+                "\nlet debug_string = format!(\"gl{name}{para1}{{:?}}{para2}\", ({debug_idents}));",
+                name = cmd.proto.ident,
+                debug_idents = {
+                    if !params.is_empty() {
+                        if params.len() < 15 {
+                            let joined = params.join(", &");
+                            if !joined.contains("callback") {
+                                format!("&{}", joined)
                             } else {
-                                // causes debug formatter to display () (fits for function call without params)
-                                "".to_owned()
+                                "\"<callback function as parameter>\"".to_owned()
                             }
-                        },
-                        para1 = if params.len() != 1 { "" } else { "(" },
-                        para2 = if params.len() != 1 { "" } else { ")" },
-                    )
-                },
-            },
-            debug_string_print = match registry.debug_prints {
-                DebugPrints::None => "".to_owned(),
-                DebugPrints::FunctionCalls => {
-                    // only print returned values if return type is not unit
-                    if cmd.proto.ty != "()" {
-                        format!("\nprintln!(\"{{}} -> {{:?}}\", debug_string, {ret});", ret = get_return_args(cmd),)
+                        } else {
+                            // because there is no debug impl for a tuple of size 16 and up
+                            "\"<too many arguments to display>\"".to_owned()
+                        }
                     } else {
-                        format!("\nprintln!(\"{{}}\", debug_string);",)
+                        // causes debug formatter to display () (fits for function call without params)
+                        "".to_owned()
                     }
                 },
-            },
-            conversions = get_conversions(cmd),
-            initializers = get_initializers(cmd),
-            ret = get_return_args(cmd),
-        )?;
+                para1 = if params.len() != 1 { "" } else { "(" },
+                para2 = if params.len() != 1 { "" } else { ")" },
+            )
+        },
+    };
+    let print = match options.debug_prints {
+        DebugPrints::None => "".to_owned(),
+        DebugPrints::FunctionCalls => {
+            // only print returned values if return type is not unit
+            if cmd.proto.ty != "()" {
+                format!("\nprintln!(\"{{}} -> {{:?}}\", debug_string, {ret});", ret = get_return_args(options, cmd),)
+            } else {
+                "\nprintln!(\"{}\", debug_string);".to_string()
+            }
+        },
+    };
+    (initializer, print)
+}
+
+/// Returns the `# Safety` requirements of the function `write_fn` creates for `cmd`, with the
+///  parameters they are about. Commands without any are safe.
+///
+/// These are the `pointer_requirements`, except for the returned parameters whose count is one of
+///  the `single_counts`, as the function passes 1 for them.
+fn safety_requirements<'a>(options: &GeneratorOptions, cmd: &'a Cmd) -> Vec<(&'a Binding, String)> {
+    let singles = single_counts(options, cmd);
+    let mut requirements = pointer_requirements(options, cmd);
+    requirements.retain(|(param, _)| !singles.iter().any(|count| param.len.as_deref() == Some(&*count.ident)));
+    requirements
+}
+
+/// Returns the requirements of the C signature of `cmd` on its parameters.
+///
+/// Parameters which are still raw pointers have requirements, as do converted and returned ones
+///  of which the command accesses as many elements as another parameter says, like `buffers` of
+///  `DeleteBuffers(n, buffers)`. Pointers behind typedefs, like `GLsync`, count as object names.
+fn pointer_requirements<'a>(options: &GeneratorOptions, cmd: &'a Cmd) -> Vec<(&'a Binding, String)> {
+    cmd.params
+        .iter()
+        .filter_map(|param| {
+            // the lengths which don't follow from the parameter itself
+            let len = param.len.as_deref().filter(|&len| len != "1" && len != format!("COMPSIZE({})", param.ident));
+            let requirement = match (return_type(options, cmd, param), conversion(options, cmd, param)) {
+                (Some(_), _) => format!("Only a single value is returned for `{}`, so `{}` must be at most 1", param.ident, len?),
+                (None, Some((rust_ty, _))) if !rust_ty.starts_with('*') => {
+                    format!("`{}` must hold at least `{}` elements", param.ident, len?)
+                },
+                _ if param.ty.starts_with('*') => match param.len.as_deref() {
+                    Some("1") => format!("`{}` must point to a valid element", param.ident),
+                    Some(len) => format!("`{}` must point to `{}` valid elements", param.ident, len),
+                    None => format!("`{}` must be valid for the accesses of the command", param.ident),
+                },
+                _ => return None,
+            };
+            Some((param, requirement))
+        })
+        .collect()
+}
+
+/// A parameter of a function of the `slices` module, which takes a slice giving the value of the
+///  parameter `count`.
+struct SliceParam<'a> {
+    param:   &'a Binding,
+    count:   &'a Binding,
+    /// The elements per unit of `count`, e.g. 3 for `count*3`.
+    factor:  usize,
+    rust_ty: String,
+    /// Shadows the slice `{param}` with the C pointer.
+    code:    String,
+    /// The number of elements of the slice `{param}`, in the unit of `count`.
+    len:     String,
+    /// Whether null is passed instead of taking the parameter, like for the lengths of strings
+    ///  which are NUL-terminated anyway.
+    null:    bool,
+}
+
+/// The names of the counts which are sizes in bytes, besides the `GLsizeiptr` ones, e.g. `imageSize`
+///  of `CompressedTexImage2D`. Only these are taken from the size of the `void` slices.
+const BYTE_COUNTS: &[&str] = &["bufSize", "imageSize", "len", "length", "size"];
+
+/// Returns the slice parameters replacing the parameters of `cmd` which have safety requirements,
+///  if all of them have their length in another parameter, e.g. `n` or `count*3`.
+///
+/// Converted slices and string vectors are kept, other typed pointers become slices and `void`
+///  pointers a `&[T]` or `&mut [u8]` whose size in bytes is the length, if the count is one of
+///  `BYTE_COUNTS` and no `type` parameter gives the size of the elements. Returned parameters, like
+///  `buffers` of `GenBuffers(n, buffers)`, become mutable slices. The lengths of string vectors,
+///  like `length` of `ShaderSource`, are passed as null.
+fn slice_params<'a>(options: &GeneratorOptions, cmd: &'a Cmd) -> Option<Vec<SliceParam<'a>>> {
+    let requirements = pointer_requirements(options, cmd);
+    if requirements.is_empty() {
+        return None;
     }
+    let typed = cmd.params.iter().any(|p| p.ident == "type_");
+    let strings = cmd.params.iter().find(|p| conversion(options, cmd, p).is_some_and(|(rust_ty, _)| rust_ty == "Vec<&str>"));
 
-    Ok(())
+    requirements
+        .into_iter()
+        .map(|(param, _)| {
+            let len = param.len.as_deref()?;
+            let (count, factor) = match len.split_once('*') {
+                Some((count, factor)) => (count, factor.parse::<usize>().ok()?),
+                None => (len, 1),
+            };
+            let count = cmd.params.iter().find(|p| p.ident == count && !p.ty.starts_with('*'))?;
+            let bytes = factor == 1 && !typed && (count.ty.starts_with("types::GLsizeiptr") || BYTE_COUNTS.contains(&&*count.ident));
+
+            let slice_len = "{param}.len()".to_string();
+            let null = strings.is_some_and(|s| s.ident != param.ident && s.len == param.len) && param.ty == "*const types::GLint";
+            let (rust_ty, code, len) = match (conversion(options, cmd, param), return_type(options, cmd, param)) {
+                _ if null => (String::new(), "let {param} = std::ptr::null();".to_string(), String::new()),
+                (Some((rust_ty, code)), None) if ["&[", "&mut [", "Vec<"].iter().any(|p| rust_ty.starts_with(p)) => {
+                    (rust_ty.to_string(), code.to_string(), slice_len)
+                },
+                (Some(_), _) => return None,
+                (None, _) => {
+                    let (mutable, pointee) = match param.ty.strip_prefix("*const ") {
+                        Some(pointee) => (false, pointee),
+                        None => (true, param.ty.strip_prefix("*mut ")?),
+                    };
+                    match (mutable, pointee) {
+                        // sizes of buffers are in bytes
+                        (false, "__gl_imports::raw::c_void") if bytes => (
+                            "&[T]".to_string(),
+                            "let {param} = {param}.as_ptr() as *const _;".to_string(),
+                            "__gl_imports::mem::size_of_val({param})".to_string(),
+                        ),
+                        (true, "__gl_imports::raw::c_void") if bytes => {
+                            ("&mut [u8]".to_string(), "let {param} = {param}.as_mut_ptr() as *mut _;".to_string(), slice_len)
+                        },
+                        (_, pointee) if pointee.contains("c_void") || pointee.contains('*') => return None,
+                        (false, pointee) => (format!("&[{}]", pointee), "let {param} = {param}.as_ptr();".to_string(), slice_len),
+                        (true, pointee) => (format!("&mut [{}]", pointee), "let {param} = {param}.as_mut_ptr();".to_string(), slice_len),
+                    }
+                },
+            };
+            Some(SliceParam {
+                param,
+                count,
+                factor,
+                rust_ty,
+                code: code.replace("{cmd}", &cmd.proto.ident),
+                len: len.replace("{param}", &param.ident),
+                null,
+            })
+        })
+        .collect()
 }
 
 lazy_static! {
@@ -217,6 +953,12 @@ let {param} = {param}_vec.as_ptr();"#.to_string()));
             "*const types::GLenum".to_string(),
             ("&[types::GLenum]".to_string(),
             r#"let {param} = {param}.as_ptr();"#.to_string()));
+        for handle in HANDLES {
+            map.insert(
+                format!("*const types::{}", handle),
+                (format!("&[types::{}]", handle),
+                r#"let {param} = {param}.as_ptr();"#.to_string()));
+        }
         map
     };
 
@@ -244,11 +986,38 @@ let {param} = &mut {param}_ret;"#.to_string())
 let mut {param}_ret = 0;
 let {param} = &mut {param}_ret;"#.to_string())
         );
+        for handle in HANDLES {
+            map.insert(
+                format!("*mut types::{}", handle),
+                (format!("types::{}", handle),
+                format!(r#"// assuming that this pointer points to only one value
+let mut {{param}}_ret = types::{}::default();
+let {{param}} = &mut {{param}}_ret;"#, handle))
+            );
+        }
         map
     };
 }
 
-fn get_return_args(cmd: &Cmd) -> String {
+/// Finds the conversion of the parameter `param` of `cmd`, preferring `GeneratorOptions::conversions`
+///  over `RUSTIFY_MAP`.
+fn conversion<'a>(options: &'a GeneratorOptions, cmd: &Cmd, param: &Binding) -> Option<(&'a str, &'a str)> {
+    match options.conversions.iter().find(|c| c.applies_to(&cmd.proto.ident, &param.ident, &param.ty)) {
+        Some(c) => Some((&c.rust_ty, &c.code)),
+        None => RUSTIFY_MAP.get(&*param.ty).map(|(ty, code)| (ty.as_str(), code.as_str())),
+    }
+}
+
+/// Finds the return conversion of the parameter `param` of `cmd`, preferring
+///  `GeneratorOptions::return_conversions` over `RETURN_TYPES`.
+fn return_type<'a>(options: &'a GeneratorOptions, cmd: &Cmd, param: &Binding) -> Option<(&'a str, &'a str)> {
+    match options.return_conversions.iter().find(|c| c.applies_to(&cmd.proto.ident, &param.ident, &param.ty)) {
+        Some(c) => Some((&c.rust_ty, &c.code)),
+        None => RETURN_TYPES.get(&*param.ty).map(|(ty, code)| (ty.as_str(), code.as_str())),
+    }
+}
+
+fn get_return_args(options: &GeneratorOptions, cmd: &Cmd) -> String {
     let mut ret = String::from("(");
     let mut i = 0;
 
@@ -258,7 +1027,7 @@ fn get_return_args(cmd: &Cmd) -> String {
     }
 
     cmd.params.iter().for_each(|param| {
-        if let Some(_) = RETURN_TYPES.get(&*param.ty) {
+        if return_type(options, cmd, param).is_some() {
             if i != 0 {
                 ret.push_str(", ");
             }
@@ -274,17 +1043,17 @@ fn get_return_args(cmd: &Cmd) -> String {
     ret
 }
 
-fn add_return_types(cmd: &Cmd, ty: String) -> String {
+fn add_return_types(options: &GeneratorOptions, cmd: &Cmd, ty: String) -> String {
     if ty == "()" {
         let mut ret = String::from("(");
         let mut i = 0;
         cmd.params.iter().for_each(|param| {
-            if let Some((ty, _)) = RETURN_TYPES.get(&*param.ty) {
+            if let Some((ty, _)) = return_type(options, cmd, param) {
                 if i != 0 {
                     ret.push_str(", ");
                 }
 
-                ret.push_str(&ty);
+                ret.push_str(ty);
                 i += 1;
             }
         });
@@ -294,9 +1063,9 @@ fn add_return_types(cmd: &Cmd, ty: String) -> String {
         let mut ret = String::from("(");
         ret.push_str(&ty);
         cmd.params.iter().for_each(|param| {
-            if let Some((ty, _)) = RETURN_TYPES.get(&*param.ty) {
+            if let Some((ty, _)) = return_type(options, cmd, param) {
                 ret.push_str(", ");
-                ret.push_str(&ty);
+                ret.push_str(ty);
             }
         });
         ret.push_str(")");
@@ -304,10 +1073,13 @@ fn add_return_types(cmd: &Cmd, ty: String) -> String {
     }
 }
 
-fn get_initializers(cmd: &Cmd) -> String {
+fn get_initializers(options: &GeneratorOptions, cmd: &Cmd) -> String {
     let mut initializers = String::from("\n");
+    for count in single_counts(options, cmd) {
+        initializers.push_str(&format!("                    // a single value is returned\n                    let {} = 1;\n", count.ident));
+    }
     cmd.params.iter().for_each(|param| {
-        if let Some((_, init_func)) = RETURN_TYPES.get(&*param.ty) {
+        if let Some((_, init_func)) = return_type(options, cmd, param) {
             initializers.push_str("                    ");
             initializers.push_str(&init_func.replace("{param}", &*param.ident).replace("\n", "\n                    "));
             initializers.push_str("\n");
@@ -320,31 +1092,30 @@ fn get_initializers(cmd: &Cmd) -> String {
     }
 }
 
-fn is_return_type(ty: &str) -> bool {
-    RETURN_TYPES.get(ty).is_some()
+/// Returns the parameters of `cmd` which only give the number of values of returned parameters,
+///  like `n` of `GenBuffers(n, buffers)`. As a single value is returned, the functions don't take
+///  them and pass 1 instead, see `get_initializers`.
+fn single_counts<'a>(options: &GeneratorOptions, cmd: &'a Cmd) -> Vec<&'a Binding> {
+    cmd.params
+        .iter()
+        .filter(|count| {
+            let mut counted = cmd.params.iter().filter(|param| param.len.as_deref() == Some(&*count.ident)).peekable();
+            !count.ty.starts_with('*') && counted.peek().is_some() && counted.all(|param| return_type(options, cmd, param).is_some())
+        })
+        .collect()
 }
 
-fn without_return_types_params(cmd: &Cmd) -> Cmd {
+fn without_return_types_params(options: &GeneratorOptions, cmd: &Cmd) -> Cmd {
+    let singles = single_counts(options, cmd);
     let mut new = cmd.clone();
-    new.params = new.params.clone();
-    //new.params.drain_filter(|param| !is_return_type(&*param.ty));
-    {
-        let mut i = 0;
-        while i != new.params.len() {
-            if is_return_type(&*new.params[i].ty) {
-                new.params.remove(i);
-            } else {
-                i += 1;
-            }
-        }
-    }
+    new.params.retain(|param| return_type(options, cmd, param).is_none() && !singles.iter().any(|count| count.ident == param.ident));
     new
 }
 
-fn get_conversions(cmd: &Cmd) -> String {
+fn get_conversions(options: &GeneratorOptions, cmd: &Cmd) -> String {
     let mut conversions = String::from("\n");
     cmd.params.iter().for_each(|param| {
-        if let Some((_, conv_func)) = RUSTIFY_MAP.get(&*param.ty) {
+        if let Some((_, conv_func)) = conversion(options, cmd, param) {
             conversions.push_str("                    ");
             conversions.push_str(&conv_func.replace("{param}", &*param.ident).replace("{cmd}", &*cmd.proto.ident).replace("\n", "\n                    "));
             conversions.push_str("\n");
@@ -357,16 +1128,470 @@ fn get_conversions(cmd: &Cmd) -> String {
     }
 }
 
-fn get_rustified(cmd: &Cmd) -> Cmd {
+fn get_rustified(options: &GeneratorOptions, cmd: &Cmd) -> Cmd {
     let mut new = cmd.clone();
     new.params.iter_mut().for_each(|param| {
-        if let Some((type_, _)) = RUSTIFY_MAP.get(&*param.ty) {
-            param.ty = Cow::Borrowed(type_.as_str());
+        if let Some((type_, _)) = conversion(options, cmd, param) {
+            param.ty = Cow::Owned(type_.to_string());
         }
     });
     new
 }
 
+/// Creates a `raw` module with an unsafe function taking the C parameters for each command in
+///  `GeneratorOptions::overrides`, so that replacements can call the command, and for the commands
+///  called by `Fence`.
+fn write_raw_fns<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    let fence_cmds = fence_cmds(registry);
+    if registry.options.overrides.is_empty() && fence_cmds.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(
+        dest,
+        "pub mod raw {{
+            #![allow(non_snake_case)]
+            use super::{{__gl_imports, storage, types}};"
+    )?;
+
+    let raw = registry.cmds.iter().enumerate().filter(|(_, cmd)| {
+        registry.options.overrides.contains_key(&cmd.proto.ident) || fence_cmds.contains(&&*cmd.proto.ident)
+    });
+    for (id, cmd) in raw {
+        writeln!(
+            dest,
+            "#[inline]
+pub unsafe fn {name}({params}) -> {return_suffix} {{
+{call}
+}}",
+            name = cmd.proto.ident,
+            params = super::gen_parameters(cmd, true, true).join(", "),
+            return_suffix = &*cmd.proto.ty,
+            call = ffi_call(&registry.options, id, cmd),
+        )?;
+    }
+
+    writeln!(dest, "}}")
+}
+
+/// Creates an `arrays` module with array-taking versions of the command families with vector
+///  equivalents.
+///
+/// Commands with a `vecequiv`, or with a `v`-suffixed counterpart like `Uniform3f`, take their
+///  components as one array, e.g. `arrays::Uniform3f(location, [x, y, z])`. Commands taking a
+///  `count` of vectors or matrices take a slice of arrays instead, e.g.
+///  `arrays::UniformMatrix4fv(location, transpose, &[[[f32; 4]; 4]])` with column-major matrices.
+///  The functions call the ones created by `write_fns`, so commands with an override or a converted
+///  component are skipped, and are `#[deprecated]` like them.
+fn write_array_fns<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    writeln!(
+        dest,
+        "pub mod arrays {{
+            #![allow(non_snake_case, deprecated, unused_imports, unused_unsafe)]
+            use super::types;"
+    )?;
+
+    let options = &registry.options;
+    for cmd in registry.cmds.iter().filter(|cmd| !options.overrides.contains_key(&cmd.proto.ident)) {
+        let (params, args, sized) = match array_params(registry, cmd) {
+            Some(array_params) => array_params,
+            None => continue,
+        };
+        // the functions are safe if the arrays satisfy the safety requirements
+        let requirements = safety_requirements(options, cmd);
+        if requirements.iter().any(|(param, _)| Some(&*param.ident) != sized) {
+            continue;
+        }
+        if let Some(feature) = registry.removed_in_core(&cmd.proto.ident) {
+            writeln!(dest, "#[deprecated(note = \"removed in core {}\")]", feature.number)?;
+        }
+        let call = format!("super::{}({})", cmd.proto.ident, args.join(", "));
+        writeln!(
+            dest,
+            "#[inline]
+pub fn {name}({params}) -> {return_suffix} {{
+{call}
+}}",
+            name = cmd.proto.ident,
+            params = params.join(", "),
+            return_suffix = add_return_types(options, cmd, cmd.proto.ty.to_string()),
+            call = if requirements.is_empty() { call } else { format!("unsafe {{ {} }}", call) },
+        )?;
+    }
+
+    writeln!(dest, "}}")
+}
+
+/// Creates a `slices` module with safe versions of the unsafe functions whose pointers all have
+///  their length in another parameter, see `slice_params`.
+///
+/// The lengths are taken from the slices, e.g. `slices::DeleteBuffers(&buffers)` or
+///  `slices::BufferData(target, &vertices, usage)`, and the other slices sharing one are asserted
+///  to be long enough. The functions call the function pointers themselves, as returned parameters
+///  become slices.
+fn write_slice_fns<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    writeln!(
+        dest,
+        "pub mod slices {{
+            #![allow(non_snake_case, unused_imports, unused_parens, unused_variables)]
+            use super::{{__gl_imports, storage, types}};"
+    )?;
+
+    let options = &registry.options;
+    for (id, cmd) in registry.cmds.iter().enumerate().filter(|(_, cmd)| !options.overrides.contains_key(&cmd.proto.ident)) {
+        let slices = match slice_params(options, cmd) {
+            Some(slices) => slices,
+            None => continue,
+        };
+        let (params, others) = slice_fn_params(options, cmd, &slices);
+        let params = params.iter().map(|(ident, ty)| format!("{}: {}", ident, ty));
+
+        let mut counts = String::new();
+        for (i, slice) in slices.iter().enumerate().filter(|(_, slice)| !slice.null) {
+            let len = match slice.factor {
+                1 => slice.len.clone(),
+                factor => format!("({} / {})", slice.len, factor),
+            };
+            match slices[..i].iter().find(|s| s.count.ident == slice.count.ident && !s.null) {
+                None => counts.push_str(&format!("let {} = {} as {};\n", slice.count.ident, len, slice.count.ty)),
+                Some(first) => counts.push_str(&format!(
+                    "assert!({len} >= {count} as usize, \"`{param}` is shorter than `{first}`\");\n",
+                    len = len,
+                    count = slice.count.ident,
+                    param = slice.param.ident,
+                    first = first.param.ident
+                )),
+            }
+        }
+
+        let mut conversions = get_conversions(options, &others);
+        for slice in &slices {
+            conversions.push_str(&format!("{}\n", slice.code.replace("{param}", &slice.param.ident)));
+        }
+
+        if let Some(feature) = registry.removed_in_core(&cmd.proto.ident) {
+            writeln!(dest, "#[deprecated(note = \"removed in core {}\")]", feature.number)?;
+        }
+        // the call has all parameters, but only the others are returned
+        let (debug_string_initializer, _) = debug_call(registry, cmd);
+        let (_, debug_string_print) = debug_call(registry, &others);
+        writeln!(
+            dest,
+            "#[inline]
+pub fn {name}{generics}({params}) -> {return_suffix} {{
+{counts}unsafe {{
+{conversions}{initializers}{debug_string_initializer}
+let func_retv = {call};\
+{debug_string_print}
+{ret}
+}}
+}}",
+            name = cmd.proto.ident,
+            generics = if slices.iter().any(|s| s.rust_ty == "&[T]") { "<T: Copy>" } else { "" },
+            params = params.collect::<Vec<_>>().join(", "),
+            return_suffix = add_return_types(options, &others, cmd.proto.ty.to_string()),
+            counts = counts,
+            conversions = conversions,
+            initializers = get_initializers(options, &others),
+            debug_string_initializer = debug_string_initializer,
+            call = ffi_call(options, id, cmd),
+            debug_string_print = debug_string_print,
+            ret = get_return_args(options, &others),
+        )?;
+    }
+
+    writeln!(dest, "}}")
+}
+
+/// Returns the parameter names and types of the `slices` function for `cmd` with the slice
+///  parameters `slices`, and `cmd` with only the parameters which aren't slices or their counts.
+fn slice_fn_params<'a>(options: &'a GeneratorOptions, cmd: &'a Cmd, slices: &'a [SliceParam]) -> (Vec<(&'a str, &'a str)>, Cmd) {
+    let slice = |param: &Binding| slices.iter().find(|s| s.param.ident == param.ident);
+    let is_count = |param: &Binding| slices.iter().any(|s| s.count.ident == param.ident);
+
+    // the converted and returned parameters which aren't slices
+    let mut others = cmd.clone();
+    others.params.retain(|p| slice(p).is_none() && !is_count(p));
+
+    let params = cmd.params.iter().filter(|p| !is_count(p)).filter_map(|p| match slice(p) {
+        Some(slice) if slice.null => None,
+        Some(slice) => Some((&*p.ident, &*slice.rust_ty)),
+        None if return_type(options, cmd, p).is_some() => None,
+        None => Some((&*p.ident, conversion(options, cmd, p).map_or(&*p.ty, |(ty, _)| ty))),
+    });
+    (params.collect(), others)
+}
+
+/// Creates a `command_buffer` module with the `CommandBuffer` type recording calls, if
+///  `GeneratorOptions::command_buffer` is set. `CommandBuffer` is re-exported at the root.
+///
+/// Commands returning nothing are recorded with the parameters of their safe function or of their
+///  `slices` function, as long as all of them can be owned and sent to other threads, see
+///  `owned_param`. Recording copies the slices and strings, and `execute` calls the functions.
+fn write_command_buffer<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    let options = &registry.options;
+    if !options.command_buffer {
+        return Ok(());
+    }
+
+    let (mut variants, mut methods, mut arms) = (String::new(), String::new(), String::new());
+    for cmd in registry.cmds.iter().filter(|cmd| !options.overrides.contains_key(&cmd.proto.ident)) {
+        let ident = &*cmd.proto.ident;
+        let (path, params, ret) = if safety_requirements(options, cmd).is_empty() {
+            let params = get_rustified(options, &without_return_types_params(options, cmd)).params;
+            let params = params.into_iter().map(|p| (p.ident.to_string(), p.ty.to_string())).collect::<Vec<_>>();
+            (format!("super::{}", ident), params, add_return_types(options, cmd, cmd.proto.ty.to_string()))
+        } else if let Some(slices) = slice_params(options, cmd) {
+            let (params, others) = slice_fn_params(options, cmd, &slices);
+            let params = params.into_iter().map(|(ident, ty)| (ident.to_string(), ty.to_string())).collect::<Vec<_>>();
+            (format!("super::slices::{}", ident), params, add_return_types(options, &others, cmd.proto.ty.to_string()))
+        } else {
+            continue;
+        };
+        if ret != "()" {
+            continue;
+        }
+        let owned = match params.iter().map(|(_, ty)| owned_param(registry, ty)).collect::<Option<Vec<_>>>() {
+            Some(owned) => owned,
+            None => continue,
+        };
+
+        let fill = |code: &str, param: &str| code.replace("{param}", param);
+        variants.push_str(&format!("    {}({}),\n", ident, owned.iter().map(|o| &*o.ty).collect::<Vec<_>>().join(", ")));
+        methods.push_str(&format!(
+            "
+    #[inline]
+    pub fn {ident}{generics}(&mut self, {params}) {{
+        self.cmds.push(Command::{ident}({records}));
+    }}
+",
+            ident = ident,
+            generics = if params.iter().any(|(_, ty)| ty == "&[T]") { "<T: Copy>" } else { "" },
+            params = params.iter().map(|(param, ty)| format!("{}: {}", param, ty)).collect::<Vec<_>>().join(", "),
+            records = params.iter().zip(&owned).map(|((param, _), o)| fill(o.record, param)).collect::<Vec<_>>().join(", "),
+        ));
+        arms.push_str(&format!(
+            "                Command::{ident}({bindings}) => {path}({args}),\n",
+            ident = ident,
+            bindings = params.iter().map(|(param, _)| format!("ref {}", param)).collect::<Vec<_>>().join(", "),
+            path = path,
+            args = params.iter().zip(&owned).map(|((param, _), o)| fill(o.replay, param)).collect::<Vec<_>>().join(", "),
+        ));
+    }
+
+    writeln!(
+        dest,
+        "/// Commands recorded on any thread, e.g. by workers loading assets, which are called on the
+///  thread of the context by `execute`.
+///
+/// Slices and strings are copied when recorded. Commands returning values or taking pointers can't
+///  be recorded.
+///
+/// ```ignore
+/// let mut cmds = gl::CommandBuffer::new();
+/// cmds.BindBuffer(gl::ARRAY_BUFFER, buffer);
+/// cmds.BufferData(gl::ARRAY_BUFFER, &vertices, gl::STATIC_DRAW);
+/// sender.send(cmds).unwrap();
+/// // on the render thread
+/// receiver.recv().unwrap().execute();
+/// ```
+pub mod command_buffer {{
+    #![allow(non_snake_case, deprecated, unused_imports)]
+    use super::*;
+
+    #[derive(Clone, Default)]
+    pub struct CommandBuffer {{
+        cmds: Vec<Command>,
+    }}
+
+    #[derive(Clone)]
+    enum Command {{
+{variants}    }}
+
+    impl CommandBuffer {{
+        pub fn new() -> CommandBuffer {{
+            CommandBuffer::default()
+        }}
+
+        /// The number of recorded commands.
+        pub fn len(&self) -> usize {{
+            self.cmds.len()
+        }}
+
+        pub fn is_empty(&self) -> bool {{
+            self.cmds.is_empty()
+        }}
+
+        /// Removes the recorded commands, keeping the allocation.
+        pub fn clear(&mut self) {{
+            self.cmds.clear()
+        }}
+
+        /// Calls the recorded commands in order. The buffer is kept so that it can be executed
+        ///  again.
+        pub fn execute(&self) {{
+            for cmd in &self.cmds {{
+                match *cmd {{
+{arms}                }}
+            }}
+        }}
+{methods}    }}
+}}
+pub use self::command_buffer::CommandBuffer;",
+        variants = variants,
+        arms = arms,
+        methods = methods,
+    )
+}
+
+/// How `CommandBuffer` stores a parameter: the owned type, and the code taking the parameter
+///  `{param}` or giving it back from `{param}`, a reference to the owned value.
+struct OwnedParam<'a> {
+    ty:     Cow<'a, str>,
+    record: &'static str,
+    replay: &'static str,
+}
+
+/// Returns how `CommandBuffer` stores a parameter of the Rust type `ty`, or `None` for mutable
+///  references and values which can't be sent to other threads.
+///
+/// The bytes of `&[T]` are copied, including the padding, so they are stored as `MaybeUninit<u8>`.
+fn owned_param<'a>(registry: &Registry, ty: &'a str) -> Option<OwnedParam<'a>> {
+    const TO_VEC: &str = "{param}.to_vec()";
+    const AS_SLICE: &str = "&{param}[..]";
+    let owned = match ty {
+        "&str" => OwnedParam { ty: "String".into(), record: "{param}.to_string()", replay: "{param}" },
+        "Vec<&str>" => OwnedParam {
+            ty:     "Vec<String>".into(),
+            record: "{param}.iter().map(|s| s.to_string()).collect()",
+            replay: "{param}.iter().map(|s| &**s).collect()",
+        },
+        "&[T]" => OwnedParam {
+            ty:     "Vec<__gl_imports::mem::MaybeUninit<u8>>".into(),
+            record: "unsafe { std::slice::from_raw_parts({param}.as_ptr() as *const __gl_imports::mem::MaybeUninit<u8>, __gl_imports::mem::size_of_val({param})) }.to_vec()",
+            replay: AS_SLICE,
+        },
+        _ if ty.starts_with("&mut ") => return None,
+        _ if ty.starts_with("&[") => {
+            let elem = &ty[2..ty.len() - 1];
+            if !is_send(registry, elem) {
+                return None;
+            }
+            OwnedParam { ty: format!("Vec<{}>", elem).into(), record: TO_VEC, replay: AS_SLICE }
+        },
+        // e.g. `&AttribList<types::EGLint>`
+        _ if ty.starts_with('&') => OwnedParam { ty: ty[1..].into(), record: "{param}.clone()", replay: "{param}" },
+        _ if is_send(registry, ty) => OwnedParam { ty: ty.into(), record: "{param}", replay: "*{param}" },
+        _ => return None,
+    };
+    Some(owned)
+}
+
+/// Whether values of the Rust type `ty` can be sent to other threads, which pointers, handles and
+///  the types containing them can't. Types defined outside of the registry are assumed not to be.
+fn is_send(registry: &Registry, ty: &str) -> bool {
+    if ty.starts_with('*') {
+        return false;
+    }
+    let ident = match ty.strip_prefix("types::") {
+        Some(ident) => ident,
+        None => return true,
+    };
+    registry.types.iter().filter(|t| t.ident == ident).all(|t| match t.kind {
+        TypeKind::Alias(ref alias) => is_send(registry, alias),
+        TypeKind::Struct(ref fields) | TypeKind::Union(ref fields) => fields.iter().all(|f| is_send(registry, &f.ty)),
+        TypeKind::FnPtr { .. } | TypeKind::Opaque => true,
+        TypeKind::Handle | TypeKind::External => false,
+    })
+}
+
+/// Returns the parameters and call arguments of the `arrays` function for `cmd`, if it has one, and
+///  the parameter whose length it takes from the arrays.
+fn array_params<'a>(registry: &Registry, cmd: &'a Cmd) -> Option<(Vec<String>, Vec<String>, Option<&'a str>)> {
+    let options = &registry.options;
+    let rustified = get_rustified(options, &without_return_types_params(options, cmd));
+    let mut params = super::gen_parameters(&rustified, true, true);
+    let mut args = super::gen_parameters(&rustified, true, false);
+
+    let vector = match cmd.vecequiv {
+        Some(ref vecequiv) => Some(&**vecequiv),
+        None => {
+            let vector = format!("{}v", cmd.proto.ident);
+            registry.cmds.iter().map(|c| &*c.proto.ident).find(|&ident| ident == vector)
+        },
+    };
+    if vector.is_some() {
+        // the trailing components, e.g. `x, y, z` of `Uniform3f(location, x, y, z)`
+        let n = component_count(&cmd.proto.ident)?;
+        if n < 2 || n > rustified.params.len() {
+            return None;
+        }
+        let first = rustified.params.len() - n;
+        let components = &rustified.params[first..];
+        let ty = &components[0].ty;
+        if components.iter().any(|p| p.ty != *ty || p.ty.starts_with('*') || conversion(options, cmd, p).is_some()) {
+            return None;
+        }
+
+        params.truncate(first);
+        params.push(format!("v: [{}; {}]", ty, n));
+        args.truncate(first);
+        args.extend((0..n).map(|i| format!("v[{}]", i)));
+        return Some((params, args, None));
+    }
+
+    // `count` vectors or matrices, e.g. `Uniform3fv(location, count, value)`
+    let count = rustified.params.iter().position(|p| p.ident == "count" && p.ty == "types::GLsizei")?;
+    let (index, value) = rustified.params.iter().enumerate().find(|(_, p)| p.len.as_ref().is_some_and(|len| len.starts_with("count*")))?;
+    let size = value.len.as_ref()?["count*".len()..].parse::<usize>().ok()?;
+    let ty = value.ty.strip_prefix("&[")?.strip_suffix(']')?;
+    if size < 2 || ty.contains(['[', ']', '&']) {
+        return None;
+    }
+    let array_ty = match matrix_dims(&cmd.proto.ident) {
+        Some((columns, rows)) if columns * rows == size => format!("[[{}; {}]; {}]", ty, rows, columns),
+        Some(_) => return None,
+        None => format!("[{}; {}]", ty, size),
+    };
+
+    params[index] = format!("{}: &[{}]", value.ident, array_ty);
+    args[index] = format!(
+        "unsafe {{ std::slice::from_raw_parts({ident}.as_ptr() as *const {ty}, {ident}.len() * {size}) }}",
+        ident = value.ident,
+        ty = ty,
+        size = size
+    );
+    args[count] = format!("{}.len() as types::GLsizei", value.ident);
+    params.remove(count);
+    let sized = cmd.params.iter().find(|p| p.ident == value.ident).map(|p| &*p.ident);
+    Some((params, args, sized))
+}
+
+/// The number of components of a scalar command, e.g. 3 for `Uniform3f`, `VertexAttribI3ui` and
+///  `Uniform3i64ARB`.
+fn component_count(ident: &str) -> Option<usize> {
+    let ident = ident.trim_end_matches(|c: char| c.is_ascii_uppercase());
+    let ident = ident.strip_suffix("64").unwrap_or(ident);
+    let ident = ident.trim_end_matches(|c: char| c.is_ascii_lowercase());
+    ident.chars().last()?.to_digit(10).map(|n| n as usize)
+}
+
+/// The columns and rows of the matrices of a command, e.g. `(2, 3)` for `UniformMatrix2x3fv`.
+fn matrix_dims(ident: &str) -> Option<(usize, usize)> {
+    let dims = &ident[ident.find("Matrix")? + "Matrix".len()..];
+    let mut digits = dims.chars().map(|c| c.to_digit(10).map(|n| n as usize));
+    let columns = digits.next()??;
+    let rows = match dims[1..].strip_prefix('x') {
+        Some(rest) => rest.chars().next()?.to_digit(10)? as usize,
+        None => columns,
+    };
+    Some((columns, rows))
+}
+
 /// Creates a `FnPtr` structure which contains the store for a single binding.
 fn write_fnptr_struct_def<W>(dest: &mut W) -> io::Result<()>
 where W: io::Write {
@@ -382,10 +1607,13 @@ pub struct FnPtr {{
 }}
 
 impl FnPtr {{
+    /// A `FnPtr` which was not loaded.
+    const MISSING: FnPtr = FnPtr {{ f: missing_fn_panic as *const __gl_imports::raw::c_void, is_loaded: false }};
+
     /// Creates a `FnPtr` from a load attempt.
     pub fn new(ptr: *const __gl_imports::raw::c_void) -> FnPtr {{
         if ptr.is_null() {{
-            FnPtr {{ f: missing_fn_panic as *const __gl_imports::raw::c_void, is_loaded: false }}
+            FnPtr::MISSING
         }} else {{
             FnPtr {{ f: ptr, is_loaded: true }}
         }}
@@ -395,80 +1623,337 @@ impl FnPtr {{
     )
 }
 
-/// Creates a `storage` module which contains a static `FnPtr` per GL command in the registry.
+/// Creates a `storage` module with tables indexed by command id: the `FnPtr`s, the symbols and the
+///  fallback symbols of the commands.
 fn write_ptrs<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
 where W: io::Write {
+    let symbols = registry.cmds.iter().map(|c| format!("\"{}\"", super::gen_symbol_name(registry.api, &c.proto.ident)));
+    let fallbacks = registry.cmds.iter().map(|c| match registry.aliases.get(&c.proto.ident) {
+        Some(v) => {
+            let names = v.iter().map(|name| format!("\"{}\"", super::gen_symbol_name(registry.api, name))).collect::<Vec<_>>();
+            format!("&[{}]", names.join(", "))
+        },
+        None => "&[]".to_string(),
+    });
+
     writeln!(
         dest,
         "mod storage {{
-            #![allow(non_snake_case)]
-            #![allow(non_upper_case_globals)]
-            use super::__gl_imports::raw;
-            use super::FnPtr;"
-    )?;
+            use super::FnPtr;
 
-    for c in &registry.cmds {
-        writeln!(
-            dest,
-            "pub static mut {name}: FnPtr = FnPtr {{
-                f: super::missing_fn_panic as *const raw::c_void,
-                is_loaded: false
-            }};",
-            name = c.proto.ident
-        )?;
+            pub static mut FNS: [FnPtr; {len}] = [FnPtr::MISSING; {len}];
+            /// Sorted by name, as the commands are.
+            pub static SYMBOLS: [&str; {len}] = [{symbols}];
+            pub static FALLBACKS: [&[&str]; {len}] = [{fallbacks}];
+        }}",
+        len = registry.cmds.len(),
+        symbols = symbols.collect::<Vec<_>>().join(", "),
+        fallbacks = fallbacks.collect::<Vec<_>>().join(", "),
+    )
+}
+
+/// Creates a `stats` module with the calls of each command recorded by the functions, if
+///  `GeneratorOptions::call_stats` is set. The numbers are atomics indexed by command id, like the
+///  `storage` tables.
+fn write_stats<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    if registry.options.call_stats == CallStats::None {
+        return Ok(());
     }
 
-    writeln!(dest, "}}")
+    writeln!(
+        dest,
+        "/// The calls of the commands since the last `reset`, e.g. to count the calls per frame.
+pub mod stats {{
+    use std::sync::atomic::{{AtomicU64, Ordering}};
+    use std::time::{{Duration, Instant}};
+    use super::storage;
+
+    /// The calls of a command.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct CommandStats {{
+        pub calls: u64,
+        /// The time spent in the command, which is zero unless the bindings were generated with
+        ///  `CallStats::Timing`.
+        pub time:  Duration,
+    }}
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: AtomicU64 = AtomicU64::new(0);
+    static CALLS: [AtomicU64; {len}] = [ZERO; {len}];
+    static NANOS: [AtomicU64; {len}] = [ZERO; {len}];
+
+    #[inline]
+    pub(super) fn record(id: usize, start: Option<Instant>) {{
+        CALLS[id].fetch_add(1, Ordering::Relaxed);
+        if let Some(start) = start {{
+            NANOS[id].fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        }}
+    }}
+
+    /// Returns the commands which were called since the last `reset` by name, e.g. `\"DrawArrays\"`,
+    ///  in the order of their names.
+    pub fn snapshot() -> Vec<(&'static str, CommandStats)> {{
+        let stats = (0..{len}).map(|id| {{
+            let calls = CALLS[id].load(Ordering::Relaxed);
+            let time = Duration::from_nanos(NANOS[id].load(Ordering::Relaxed));
+            (&storage::SYMBOLS[id][{prefix_len}..], CommandStats {{ calls, time }})
+        }});
+        stats.filter(|(_, stats)| stats.calls != 0).collect()
+    }}
+
+    /// Clears the recorded calls.
+    pub fn reset() {{
+        for id in 0..{len} {{
+            CALLS[id].store(0, Ordering::Relaxed);
+            NANOS[id].store(0, Ordering::Relaxed);
+        }}
+    }}
+}}",
+        len = registry.cmds.len(),
+        prefix_len = super::gen_symbol_name(registry.api, "").len()
+    )
 }
 
-/// Creates one module for each GL command.
+/// The commands whose calls are skipped if they wouldn't change the state shadowed by the `shadow`
+///  module, with the names of their parameters and the code updating the `State` `s`, which returns
+///  whether the state changed.
+const SHADOW_SETTERS: &[(&str, &[&str], &str)] = &[
+    ("UseProgram", &["program"], "set(&mut s.program, program)"),
+    // the element array buffer binding is part of the vertex array
+    (
+        "BindVertexArray",
+        &["array"],
+        "let changed = set(&mut s.vertex_array, array);
+if changed {
+    s.buffers.retain(|&(target, _)| target != ELEMENT_ARRAY_BUFFER);
+}
+changed",
+    ),
+    ("BindBuffer", &["target", "buffer"], "set_keyed(&mut s.buffers, target, buffer)"),
+    ("ActiveTexture", &["unit"], "set(&mut s.active_texture, unit)"),
+    (
+        "BindTexture",
+        &["target", "texture"],
+        "match s.active_texture {
+    Some(unit) => set_keyed(&mut s.textures, (unit, target), texture),
+    None => true,
+}",
+    ),
+    ("Enable", &["cap"], "set_keyed(&mut s.caps, cap, true)"),
+    ("Disable", &["cap"], "set_keyed(&mut s.caps, cap, false)"),
+    ("BlendFunc", &["src", "dst"], "set(&mut s.blend_func, (src, dst, src, dst))"),
+    ("BlendFuncSeparate", &["src_rgb", "dst_rgb", "src_alpha", "dst_alpha"], "set(&mut s.blend_func, (src_rgb, dst_rgb, src_alpha, dst_alpha))"),
+    ("BlendEquation", &["mode"], "set(&mut s.blend_equation, (mode, mode))"),
+    ("BlendEquationSeparate", &["mode_rgb", "mode_alpha"], "set(&mut s.blend_equation, (mode_rgb, mode_alpha))"),
+    ("DepthFunc", &["func"], "set(&mut s.depth_func, func)"),
+    ("DepthMask", &["flag"], "set(&mut s.depth_mask, flag)"),
+];
+
+/// The commands which change the state shadowed by the `shadow` module in ways it doesn't track,
+///  with the code forgetting that state of the `State` `s`.
+const SHADOW_INVALIDATORS: &[(&str, &str)] = &[
+    ("DeleteProgram", "s.program = None"),
+    ("DeleteVertexArrays", "s.vertex_array = None;\ns.buffers.retain(|&(target, _)| target != ELEMENT_ARRAY_BUFFER)"),
+    ("DeleteBuffers", "s.buffers.clear()"),
+    ("BindBufferBase", "s.buffers.clear()"),
+    ("BindBufferRange", "s.buffers.clear()"),
+    ("BindBuffersBase", "s.buffers.clear()"),
+    ("BindBuffersRange", "s.buffers.clear()"),
+    ("DeleteTextures", "s.textures.clear()"),
+    ("BindTextureUnit", "s.textures.clear()"),
+    ("BindTextures", "s.textures.clear()"),
+    ("Enablei", "s.caps.clear()"),
+    ("Disablei", "s.caps.clear()"),
+    ("BlendFunci", "s.blend_func = None"),
+    ("BlendFuncSeparatei", "s.blend_func = None"),
+    ("BlendEquationi", "s.blend_equation = None"),
+    ("BlendEquationSeparatei", "s.blend_equation = None"),
+];
+
+/// Returns whether `ffi_call` calls the `shadow` module for the command `ident`.
+fn shadowed(ident: &str) -> bool {
+    SHADOW_SETTERS.iter().any(|(cmd, _, _)| *cmd == ident) || SHADOW_INVALIDATORS.iter().any(|(cmd, _)| *cmd == ident)
+}
+
+/// Creates a `shadow` module with the state set by the commands of `SHADOW_SETTERS`, if
+///  `GeneratorOptions::shadow_state` is set and the registry has any `shadowed` command. The
+///  functions of the commands call it through `ffi_call`.
 ///
-/// Each module contains `is_loaded` and `load_with` which interact with the `storage` module
-///  created by `write_ptrs`.
-fn write_fn_mods<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
+/// The object names have the types of the command parameters, so that they are newtypes with
+///  `GeneratorOptions::object_handles`.
+fn write_shadow_state<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
 where W: io::Write {
-    for c in &registry.cmds {
-        let fallbacks = match registry.aliases.get(&c.proto.ident) {
-            Some(v) => {
-                let names = v.iter().map(|name| format!("\"{}\"", super::gen_symbol_name(registry.api, &name[..]))).collect::<Vec<_>>();
-                format!("&[{}]", names.join(", "))
-            },
-            None => "&[]".to_string(),
+    let cmd = |ident: &str| registry.cmds.iter().find(|cmd| cmd.proto.ident == ident);
+    if !registry.options.shadow_state || !registry.cmds.iter().any(|cmd| shadowed(&cmd.proto.ident)) {
+        return Ok(());
+    }
+    let name_ty = |ident: &str, i: usize| cmd(ident).map_or("types::GLuint".to_string(), |cmd| cmd.params[i].ty.to_string());
+
+    let indent = |code: &str| code.lines().map(|line| format!("            {}", line)).collect::<Vec<_>>().join("\n");
+    let mut fns = String::new();
+    for (ident, params, code) in SHADOW_SETTERS {
+        let cmd = match cmd(ident) {
+            Some(cmd) => cmd,
+            None => continue,
         };
-        let fnname = &c.proto.ident[..];
-        let symbol = super::gen_symbol_name(registry.api, &c.proto.ident[..]);
-        let symbol = &symbol[..];
+        let params = params.iter().zip(&cmd.params).map(|(param, p)| format!("{}: {}", param, p.ty)).collect::<Vec<_>>();
+        fns.push_str(&format!(
+            "
+    #[inline]
+    pub(super) fn {ident}({params}) -> bool {{
+        with(|s| {{
+{code}
+        }})
+    }}
+",
+            ident = ident,
+            params = params.join(", "),
+            code = indent(code),
+        ));
+    }
+    for (ident, code) in SHADOW_INVALIDATORS.iter().filter(|(ident, _)| cmd(ident).is_some()) {
+        fns.push_str(&format!(
+            "
+    #[inline]
+    pub(super) fn {}() {{
+        with(|s| {{
+{};
+        }})
+    }}
+",
+            ident,
+            indent(code)
+        ));
+    }
 
-        writeln!(
-            dest,
-            r##"
-            #[allow(non_snake_case)]
-            pub mod {fnname} {{
-                use super::{{storage, metaloadfn}};
-                use super::__gl_imports::raw;
-                use super::FnPtr;
-
-                #[inline]
-                #[allow(dead_code)]
-                pub fn is_loaded() -> bool {{
-                    unsafe {{ storage::{fnname}.is_loaded }}
-                }}
-
-                #[allow(dead_code)]
-                pub fn load_with<F>(mut loadfn: F) where F: FnMut(&'static str) -> *const raw::c_void {{
-                    unsafe {{
-                        storage::{fnname} = FnPtr::new(metaloadfn(&mut loadfn, "{symbol}", {fallbacks}))
-                    }}
-                }}
-            }}
-        "##,
-            fnname = fnname,
-            fallbacks = fallbacks,
-            symbol = symbol
-        )?;
+    writeln!(
+        dest,
+        "/// The bindings and other state set by the functions, which skip the calls that wouldn't change
+///  it: the program, vertex array, buffers per target, textures per unit and target, enabled
+///  capabilities, and the blend and depth functions.
+///
+/// The state is kept per thread, for the context current on it. Call `invalidate` after changing
+///  it without these functions, e.g. with other GL code or by making another context current.
+pub mod shadow {{
+    #![allow(non_snake_case, dead_code)]
+    use std::cell::RefCell;
+    use std::sync::atomic::{{AtomicU64, Ordering}};
+    use super::{{storage, types}};
+
+    const ELEMENT_ARRAY_BUFFER: types::GLenum = 0x8893;
+
+    /// The known state, `None` or missing from the lists if unknown.
+    #[derive(Default)]
+    struct State {{
+        program:        Option<{program}>,
+        vertex_array:   Option<{vertex_array}>,
+        buffers:        Vec<(types::GLenum, {buffer})>,
+        active_texture: Option<types::GLenum>,
+        textures:       Vec<((types::GLenum, types::GLenum), {texture})>,
+        caps:           Vec<(types::GLenum, bool)>,
+        blend_func:     Option<(types::GLenum, types::GLenum, types::GLenum, types::GLenum)>,
+        blend_equation: Option<(types::GLenum, types::GLenum)>,
+        depth_func:     Option<types::GLenum>,
+        depth_mask:     Option<types::GLboolean>,
+    }}
+
+    thread_local! {{
+        static STATE: RefCell<State> = RefCell::new(State::default());
+    }}
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: AtomicU64 = AtomicU64::new(0);
+    static SKIPPED: [AtomicU64; {len}] = [ZERO; {len}];
+
+    fn with<R, F: FnOnce(&mut State) -> R>(f: F) -> R {{
+        STATE.with(|state| f(&mut state.borrow_mut()))
+    }}
+
+    /// Sets `cached` to `value`, returning whether it changed.
+    fn set<T: PartialEq>(cached: &mut Option<T>, value: T) -> bool {{
+        let changed = cached.as_ref() != Some(&value);
+        *cached = Some(value);
+        changed
+    }}
+
+    /// Sets the value of `key` in `cached` to `value`, returning whether it changed.
+    fn set_keyed<K: PartialEq, T: PartialEq>(cached: &mut Vec<(K, T)>, key: K, value: T) -> bool {{
+        match cached.iter_mut().find(|(k, _)| *k == key) {{
+            Some((_, cached)) if *cached == value => false,
+            Some((_, cached)) => {{
+                *cached = value;
+                true
+            }},
+            None => {{
+                cached.push((key, value));
+                true
+            }},
+        }}
+    }}
+
+    #[inline]
+    pub(super) fn skip(id: usize) {{
+        SKIPPED[id].fetch_add(1, Ordering::Relaxed);
+    }}
+
+    /// Forgets the state of the context current on this thread, so that the next calls aren't
+    ///  skipped.
+    pub fn invalidate() {{
+        with(|s| *s = State::default())
+    }}
+
+    /// Returns the number of skipped calls of the commands since the last `reset_skipped` by name,
+    ///  e.g. `\"UseProgram\"`, on all threads.
+    pub fn skipped() -> Vec<(&'static str, u64)> {{
+        let skipped = (0..{len}).map(|id| (&storage::SYMBOLS[id][{prefix_len}..], SKIPPED[id].load(Ordering::Relaxed)));
+        skipped.filter(|&(_, skipped)| skipped != 0).collect()
+    }}
+
+    /// Clears the numbers of skipped calls.
+    pub fn reset_skipped() {{
+        for skipped in SKIPPED.iter() {{
+            skipped.store(0, Ordering::Relaxed);
+        }}
+    }}
+{fns}}}",
+        program = name_ty("UseProgram", 0),
+        vertex_array = name_ty("BindVertexArray", 0),
+        buffer = name_ty("BindBuffer", 1),
+        texture = name_ty("BindTexture", 1),
+        len = registry.cmds.len(),
+        prefix_len = super::gen_symbol_name(registry.api, "").len(),
+        fns = fns,
+    )
+}
+
+/// The types of objects tracked by the `objects` module, with the commands creating and deleting
+///  them.
+const TRACKED_OBJECTS: &[(&str, &[&str], &[&str])] = &[
+    ("Buffer", &["GenBuffers", "CreateBuffers"], &["DeleteBuffers"]),
+    ("Texture", &["GenTextures", "CreateTextures"], &["DeleteTextures"]),
+    ("VertexArray", &["GenVertexArrays", "CreateVertexArrays"], &["DeleteVertexArrays"]),
+    ("Framebuffer", &["GenFramebuffers", "CreateFramebuffers"], &["DeleteFramebuffers"]),
+    ("Renderbuffer", &["GenRenderbuffers", "CreateRenderbuffers"], &["DeleteRenderbuffers"]),
+    ("Sampler", &["GenSamplers", "CreateSamplers"], &["DeleteSamplers"]),
+    ("Query", &["GenQueries", "CreateQueries"], &["DeleteQueries"]),
+    ("TransformFeedback", &["GenTransformFeedbacks", "CreateTransformFeedbacks"], &["DeleteTransformFeedbacks"]),
+    ("ProgramPipeline", &["GenProgramPipelines", "CreateProgramPipelines"], &["DeleteProgramPipelines"]),
+    ("Shader", &["CreateShader"], &["DeleteShader"]),
+    ("Program", &["CreateProgram", "CreateShaderProgramv"], &["DeleteProgram"]),
+];
+
+/// Creates an `objects` module with the objects created by the commands of `TRACKED_OBJECTS`, if
+///  `GeneratorOptions::track_objects` is set and the registry has any of them. The functions of the
+///  commands call it through `ffi_call`.
+fn write_objects<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    let mut tracked = TRACKED_OBJECTS.iter().flat_map(|(_, creators, deleters)| creators.iter().chain(deleters.iter()));
+    if !registry.options.track_objects || !tracked.any(|ident| registry.cmds.iter().any(|cmd| cmd.proto.ident == *ident)) {
+        return Ok(());
     }
 
-    Ok(())
+    writeln!(dest, "{}", include_str!("templates/objects.rs"))
 }
 
 /// Creates a `missing_fn_panic` function.
@@ -487,9 +1972,8 @@ where W: io::Write {
     )
 }
 
-/// Creates the `load_with` function.
-///
-/// The function calls `load_with` in each module created by `write_fn_mods`.
+/// Creates the `load_with` and `is_loaded` functions, which work on the `storage` tables created by
+///  `write_ptrs`.
 fn write_load_fn<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
 where W: io::Write {
     writeln!(
@@ -502,17 +1986,407 @@ where W: io::Write {
         /// ~~~
         #[allow(dead_code)]
         pub fn load_with<F>(mut loadfn: F) where F: FnMut(&'static str) -> *const __gl_imports::raw::c_void {{
-    "
-    )?;
-
-    for c in &registry.cmds {
-        writeln!(dest, "{cmd_name}::load_with(&mut loadfn);", cmd_name = &c.proto.ident[..])?;
-    }
+            for id in 0..storage::SYMBOLS.len() {{
+                let ptr = metaloadfn(&mut loadfn, storage::SYMBOLS[id], storage::FALLBACKS[id]);
+                unsafe {{ storage::FNS[id] = FnPtr::new(ptr) }}
+            }}
+        }}
 
-    writeln!(
-        dest,
-        "
+        /// Returns whether the command `name`, e.g. `\"BindBuffer\"`, was loaded.
+        #[allow(dead_code)]
+        pub fn is_loaded(name: &str) -> bool {{
+            match storage::SYMBOLS.binary_search_by(|symbol| symbol[{prefix_len}..].cmp(name)) {{
+                Ok(id) => unsafe {{ storage::FNS[id].is_loaded }},
+                Err(_) => false,
+            }}
         }}
-    "
+    ",
+        prefix_len = super::gen_symbol_name(registry.api, "").len()
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use registry::tests::buffer_registry;
+    use {Api, CallStats, CommandOverride, GeneratorOptions, GlobalTypedGenerator, Profile, RegistryBuilder, TypeConversion};
+
+    fn bindings(options: GeneratorOptions) -> String {
+        let mut registry = buffer_registry("1.1", Profile::Core);
+        registry.options = options;
+        let mut dest = Vec::new();
+        registry.write_bindings(GlobalTypedGenerator, &mut dest).unwrap();
+        String::from_utf8(dest).unwrap()
+    }
+
+    /// Writes the bindings for the commands `cmds` of the real GL 4.6 compatibility registry.
+    fn bindings_of(cmds: &[&str], options: GeneratorOptions) -> String {
+        let mut registry = RegistryBuilder::new(Api::Gl, (4, 6)).profile(Profile::Compatibility).options(options).build().unwrap();
+        registry.cmds.retain(|cmd| cmds.contains(&&*cmd.proto.ident));
+        let mut dest = Vec::new();
+        registry.write_bindings(GlobalTypedGenerator, &mut dest).unwrap();
+        String::from_utf8(dest).unwrap()
+    }
+
+    #[test]
+    fn test_layout() {
+        let bindings = bindings(GeneratorOptions::default());
+        assert!(bindings.contains("pub mod gl_version_1_0 {"));
+        assert!(bindings.contains("pub use self::gl_version_1_1::*;"));
+        assert!(!bindings.contains("pub mod other {"));
+        // no inner attributes at the root, so the bindings can be `include!`d
+        assert!(!bindings.trim_start().starts_with("#!"));
+
+        let version_1_1 = &bindings[bindings.find("pub mod gl_version_1_1 {").unwrap()..];
+        assert!(version_1_1[..version_1_1.find("\n}").unwrap()].contains("pub fn DrawArrays("));
+        // ids are the positions in the sorted tables
+        assert!(bindings.contains("pub static SYMBOLS: [&str; 4] = [\"glBegin\", \"glBufferData\", \"glDrawArrays\", \"glGetIntegerv\"];"));
+        assert!(bindings.contains("(storage::FNS[2].f)(mode, first, count)"));
+        assert!(bindings.contains("pub static FALLBACKS: [&[&str]; 4] = [&[], &[], &[\"glDrawArraysEXT\"], &[]];"));
+    }
+
+    #[test]
+    fn test_types() {
+        let bindings = bindings(GeneratorOptions::default());
+        assert!(bindings.contains("pub type GLenum = __gl_imports::raw::c_uint;"));
+        assert!(bindings.contains("#[cfg(target_os = \"macos\")] pub type GLhandleARB = *mut __gl_imports::raw::c_void;"));
+        assert!(bindings.contains("pub enum __GLsync {}"));
+        assert!(bindings.contains("pub type GLDEBUGPROC = extern \"system\" fn(source: types::GLenum, length: types::GLsizei, message: *const types::GLchar, userParam: *const __gl_imports::raw::c_void);"));
+        assert!(bindings.contains("#[repr(C)]\n#[derive(Clone, Copy)]\npub struct GLrect {\n    pub x: types::GLint,\n"));
+    }
+
+    #[test]
+    fn test_deprecated() {
+        let write = |version| {
+            let mut dest = Vec::new();
+            buffer_registry(version, Profile::Compatibility).write_bindings(GlobalTypedGenerator, &mut dest).unwrap();
+            String::from_utf8(dest).unwrap()
+        };
+
+        let compat = write("3.2");
+        assert!(compat.contains("#[deprecated(note = \"removed in core 3.2\")]\n#[inline]\npub fn Begin("));
+        assert_eq!(compat.matches("#[deprecated").count(), 1);
+        // the removal is not known before 3.2
+        assert!(!write("1.1").contains("#[deprecated"));
+    }
+
+    #[test]
+    fn test_egl_and_glx() {
+        let write = |api, version| {
+            let mut dest = Vec::new();
+            RegistryBuilder::new(api, version).build().unwrap().write_bindings(GlobalTypedGenerator, &mut dest).unwrap();
+            String::from_utf8(dest).unwrap()
+        };
+
+        let egl = write(Api::Egl, (1, 5));
+        assert!(egl.contains("pub struct AttribList<T> {"));
+        assert!(egl.contains("impl Attrib for types::EGLint {"));
+        // defined the same way by every eglplatform.h
+        assert!(egl.contains("pub type EGLint = i32;"));
+        assert!(egl.contains("pub fn Initialize(dpy: types::EGLDisplay) -> (types::EGLBoolean, types::EGLint, types::EGLint)"));
+        assert!(egl.contains(
+            "pub fn ChooseConfig(dpy: types::EGLDisplay, attrib_list: &AttribList<types::EGLint>, configs: &mut [types::EGLConfig]) -> (types::EGLBoolean, types::EGLint)"
+        ));
+        assert!(egl.contains("pub fn GetConfigs(dpy: types::EGLDisplay, configs: &mut [types::EGLConfig]) -> (types::EGLBoolean, types::EGLint)"));
+        assert!(egl.contains("pub unsafe fn ChooseConfig(dpy: types::EGLDisplay, attrib_list: *const types::EGLint, configs: *mut types::EGLConfig"));
+        // handles are newtypes
+        assert!(egl.contains("pub struct EGLDisplay(pub *mut __gl_imports::raw::c_void);"));
+        assert!(!egl.contains("pub type EGLDisplay ="));
+        assert!(egl.contains("pub const NO_DISPLAY: types::EGLDisplay = types::EGLDisplay(0 as *mut __gl_imports::raw::c_void);"));
+        assert!(egl.contains("attrib_list: &AttribList<types::EGLAttrib>) -> (types::EGLDisplay)"));
+        assert!(egl.contains("pub fn GetProcAddress(procname: &str)"));
+
+        let glx = write(Api::Glx, (1, 4));
+        assert!(glx.contains("impl Attrib for __gl_imports::raw::c_int {"));
+        assert!(glx.contains("pub unsafe fn QueryVersion(dpy: *mut types::Display) -> (types::Bool, __gl_imports::raw::c_int, __gl_imports::raw::c_int)"));
+        assert!(glx.contains("attrib_list: &AttribList<__gl_imports::raw::c_int>) -> (*mut types::GLXFBConfig, __gl_imports::raw::c_int)"));
+
+        assert!(!write(Api::Gl, (1, 0)).contains("AttribList"));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let default = bindings(GeneratorOptions::default());
+        assert!(default.contains("pub unsafe fn BufferData(target: types::GLenum, size: types::GLsizeiptr, data: *const __gl_imports::raw::c_void, usage: types::GLenum) -> ()"));
+        assert!(default.contains("pub unsafe fn GetIntegerv(pname: types::GLenum) -> (types::GLint)"));
+        assert!(!default.contains("pub mod raw"));
+
+        let converted = bindings(GeneratorOptions {
+            conversions: vec![TypeConversion::new("*const __gl_imports::raw::c_void", "&[u8]", "let {param} = {param}.as_ptr() as *const _; // {cmd}")
+                .command("BufferData")
+                .param("data")],
+            return_conversions: vec![TypeConversion::new(
+                "*mut types::GLint",
+                "[types::GLint; 4]",
+                "let mut {param}_ret = [0; 4];\nlet {param} = {param}_ret.as_mut_ptr();",
+            )
+            .command("GetIntegerv")],
+            ..Default::default()
+        });
+        assert!(converted.contains("pub unsafe fn BufferData(target: types::GLenum, size: types::GLsizeiptr, data: &[u8], usage: types::GLenum) -> ()"));
+        assert!(converted.contains("let data = data.as_ptr() as *const _; // BufferData"));
+        assert!(converted.contains("pub unsafe fn GetIntegerv(pname: types::GLenum) -> ([types::GLint; 4])"));
+        assert!(converted.contains("let mut data_ret = [0; 4];"));
+    }
+
+    #[test]
+    fn test_component_count() {
+        assert_eq!(super::component_count("Uniform3f"), Some(3));
+        assert_eq!(super::component_count("VertexAttribI4ui"), Some(4));
+        assert_eq!(super::component_count("Uniform2i64ARB"), Some(2));
+        assert_eq!(super::component_count("VertexAttrib4Nub"), None);
+        assert_eq!(super::matrix_dims("UniformMatrix4fv"), Some((4, 4)));
+        assert_eq!(super::matrix_dims("ProgramUniformMatrix2x3dv"), Some((2, 3)));
+        assert_eq!(super::matrix_dims("Uniform3fv"), None);
+    }
+
+    #[test]
+    fn test_array_fns() {
+        let mut options = GeneratorOptions::default();
+        options.overrides.insert("Uniform4f".to_string(), CommandOverride::Suppress);
+        let bindings = bindings_of(
+            &["Color3f", "Color3fv", "Uniform3f", "Uniform3fv", "Uniform4f", "Uniform4fv", "UniformMatrix2x3fv", "VertexAttrib2s", "VertexAttrib2sv"],
+            options,
+        );
+
+        assert!(bindings.contains("pub fn Uniform3f(location: types::GLint, v: [types::GLfloat; 3]) -> () {\nsuper::Uniform3f(location, v[0], v[1], v[2])"));
+        assert!(bindings.contains("pub fn Uniform3fv(location: types::GLint, value: &[[types::GLfloat; 3]]) -> () {\nunsafe { super::Uniform3fv(location, value.len() as types::GLsizei, unsafe {"));
+        assert!(bindings.contains(
+            "pub fn UniformMatrix2x3fv(location: types::GLint, transpose: types::GLboolean, value: &[[[types::GLfloat; 3]; 2]]) -> ()"
+        ));
+        assert!(bindings.contains("value.len() * 6"));
+        // `vecequiv` from the registry
+        assert!(bindings.contains("pub fn VertexAttrib2s(index: types::GLuint, v: [types::GLshort; 2]) -> ()"));
+        // overridden
+        assert!(!bindings.contains("pub fn Uniform4f(location: types::GLint, v:"));
+
+        let arrays = &bindings[bindings.find("pub mod arrays {").unwrap()..];
+        assert!(arrays.contains("#[deprecated(note = \"removed in core 3.2\")]\n#[inline]\npub fn Color3f(v: [types::GLfloat; 3])"));
+        assert!(!arrays.contains("#[deprecated(note = \"removed in core 3.2\")]\n#[inline]\npub fn Uniform3f("));
+    }
+
+    #[test]
+    fn test_object_handles() {
+        let cmds = [
+            "BindBuffer", "GenBuffers", "DeleteBuffers", "CreateShader", "AttachShader", "UseProgram", "BindTexture", "BeginQuery",
+            "BindVertexArray", "VertexArrayVertexBuffer", "FramebufferTexture2D", "DebugMessageInsert", "ActiveTexture",
+        ];
+        let plain = bindings_of(&cmds, GeneratorOptions::default());
+        assert!(plain.contains("pub fn BindBuffer(target: types::GLenum, buffer: types::GLuint)"));
+        assert!(!plain.contains("pub struct Buffer"));
+
+        let bindings = bindings_of(&cmds, GeneratorOptions {
+            object_handles: true,
+            ..Default::default()
+        });
+        assert!(bindings.contains("pub struct Buffer(pub GLuint);"));
+        assert!(bindings.contains("pub use self::types::{Buffer, Texture, Program, Shader, VertexArray, Framebuffer, Renderbuffer, Sampler, Query};"));
+        assert!(bindings.contains("pub fn BindBuffer(target: types::GLenum, buffer: types::Buffer)"));
+        assert!(bindings.contains("pub fn GenBuffers() -> (types::Buffer)"));
+        assert!(bindings.contains("pub unsafe fn DeleteBuffers(n: types::GLsizei, buffers: &[types::Buffer])"));
+        assert!(bindings.contains("pub fn CreateShader(type_: types::GLenum) -> (types::Shader)"));
+        assert!(bindings.contains("pub fn AttachShader(program: types::Program, shader: types::Shader)"));
+        assert!(bindings.contains("pub fn UseProgram(program: types::Program)"));
+        assert!(bindings.contains("pub fn BindTexture(target: types::GLenum, texture: types::Texture)"));
+        assert!(bindings.contains("pub fn BeginQuery(target: types::GLenum, id: types::Query)"));
+        assert!(bindings.contains("pub fn BindVertexArray(array: types::VertexArray)"));
+        assert!(bindings.contains("pub fn VertexArrayVertexBuffer(vaobj: types::VertexArray, bindingindex: types::GLuint, buffer: types::Buffer"));
+        assert!(bindings.contains("pub fn FramebufferTexture2D(target: types::GLenum, attachment: types::GLenum, textarget: types::GLenum, texture: types::Texture"));
+        // not object names
+        assert!(bindings.contains("pub unsafe fn DebugMessageInsert(source: types::GLenum, type_: types::GLenum, id: types::GLuint"));
+        assert!(bindings.contains("pub fn ActiveTexture(texture: types::GLenum)"));
+    }
+
+    #[test]
+    fn test_safety() {
+        let bindings = bindings(GeneratorOptions::default());
+        assert!(bindings.contains("pub fn DrawArrays(mode: types::GLenum, first: types::GLint, count: types::GLsizei) -> ()"));
+        assert!(bindings.contains(
+            "/// # Safety\n///\n/// - `data` must point to `size` valid elements.\n///\n/// `slices::BufferData` takes the lengths from slices instead.\n#[inline]\npub unsafe fn BufferData("
+        ));
+        assert!(bindings.contains("/// - Only a single value is returned for `data`, so `COMPSIZE(pname)` must be at most 1."));
+        assert!(bindings.contains("pub fn BufferData<T: Copy>(target: types::GLenum, data: &[T], usage: types::GLenum) -> () {"));
+        assert!(bindings.contains("let size = __gl_imports::mem::size_of_val(data) as types::GLsizeiptr;"));
+        // `COMPSIZE(pname)` isn't the length of a slice
+        assert!(!bindings.contains("pub fn GetIntegerv(pname: types::GLenum, data: &mut"));
+
+        let cmds = ["GenBuffers", "Uniform3fv", "ShaderSource", "VertexAttribPointer", "DrawElementsInstancedBaseInstance", "ReadnPixels"];
+        let bindings = bindings_of(&cmds, GeneratorOptions {
+            object_handles: true,
+            ..Default::default()
+        });
+        let slices = &bindings[bindings.find("pub mod slices {").unwrap()..];
+        assert!(slices.contains("pub fn GenBuffers(buffers: &mut [types::Buffer]) -> () {\nlet n = buffers.len() as types::GLsizei;"));
+        assert!(slices.contains("pub fn Uniform3fv(location: types::GLint, value: &[types::GLfloat]) -> () {\nlet count = (value.len() / 3) as types::GLsizei;"));
+        // the strings are NUL-terminated
+        assert!(slices.contains("pub fn ShaderSource(shader: types::Shader, string: Vec<&str>) -> ()"));
+        assert!(slices.contains("let length = std::ptr::null();"));
+        assert!(!slices.contains("fn VertexAttribPointer("));
+        // `count` isn't in bytes, and `type` gives the size of the elements
+        assert!(!slices.contains("fn DrawElementsInstancedBaseInstance"));
+        assert!(!slices.contains("fn ReadnPixels"));
+
+        // a single name is returned, so `n` is 1
+        assert!(bindings.contains("pub fn GenBuffers() -> (types::Buffer) {"));
+        assert!(bindings.contains("// a single value is returned\n                    let n = 1;"));
+    }
+
+    #[test]
+    fn test_mapping_fns() {
+        let bindings = bindings_of(&["MapBuffer", "MapBufferRange", "UnmapBuffer", "GetBufferParameteri64v"], GeneratorOptions::default());
+        assert!(bindings.contains("pub struct MappedBuffer<T>"));
+        assert!(bindings.contains("pub unsafe fn MapBuffer<T: Copy>("));
+        assert!(bindings.contains("pub unsafe fn MapBufferRange<T: Copy>("));
+        // the pointer is kept without the unmapping command
+        let bindings = bindings_of(&["MapBufferRange", "MapNamedBufferRange", "UnmapNamedBuffer"], GeneratorOptions::default());
+        assert!(!bindings.contains("fn MapBufferRange<T: Copy>("));
+        assert!(bindings.contains("pub unsafe fn MapNamedBufferRange<T: Copy>("));
+
+        let mut options = GeneratorOptions::default();
+        options.overrides.insert("MapBufferRange".to_string(), CommandOverride::Suppress);
+        let bindings = bindings_of(&["MapBufferRange", "UnmapBuffer"], options);
+        assert!(!bindings.contains("MappedBuffer"));
+    }
+
+    #[test]
+    fn test_fence() {
+        let sync = ["FenceSync", "ClientWaitSync", "WaitSync", "GetSynciv", "DeleteSync"];
+        let bindings = bindings_of(&sync, GeneratorOptions::default());
+        assert!(bindings.contains("pub struct Fence"));
+        assert!(bindings.contains("pub unsafe fn FenceSync("));
+        assert!(bindings.contains("pub unsafe fn DeleteSync("));
+
+        let bindings = bindings_of(&sync[..4], GeneratorOptions::default());
+        assert!(!bindings.contains("Fence {"));
+        assert!(!bindings.contains("pub mod raw {"));
+    }
+
+    /// Writes the bindings of a GL 4.6 core registry trimmed to the identifiers `only`.
+    fn bindings_only(only: &[&str], options: GeneratorOptions) -> String {
+        let registry = RegistryBuilder::new(Api::Gl, (4, 6)).profile(Profile::Core).options(options).only(only).build().unwrap();
+        let mut dest = Vec::new();
+        registry.write_bindings(GlobalTypedGenerator, &mut dest).unwrap();
+        String::from_utf8(dest).unwrap()
+    }
+
+    #[test]
+    fn test_only() {
+        let bindings = bindings_only(&["MapBuffer"], GeneratorOptions::default());
+        assert!(bindings.contains("&[\"glMapBufferARB\", \"glMapBufferOES\"]"));
+        // kept for `MappedBuffer`, with its own fallbacks
+        assert!(bindings.contains("&[\"glUnmapBufferARB\", \"glUnmapBufferOES\"]"));
+
+        // the `shadow` module of a command invalidating the state
+        let bindings = bindings_only(&["BindBufferBase"], GeneratorOptions {
+            shadow_state: true,
+            ..Default::default()
+        });
+        assert!(bindings.contains("super::shadow::BindBufferBase()"));
+        assert!(bindings.contains("pub mod shadow {"));
+    }
+
+    #[test]
+    fn test_call_stats() {
+        assert!(!bindings(GeneratorOptions::default()).contains("pub mod stats {"));
+
+        let counts = bindings(GeneratorOptions {
+            call_stats: CallStats::Counts,
+            ..Default::default()
+        });
+        assert!(counts.contains("pub mod stats {"));
+        assert!(counts.contains("super::stats::record(2, None)"));
+        assert!(!counts.contains("Instant::now()"));
+
+        let timing = bindings(GeneratorOptions {
+            call_stats: CallStats::Timing,
+            ..Default::default()
+        });
+        assert!(timing.contains("super::stats::record(2, Some(stats_start))"));
+    }
+
+    #[test]
+    fn test_command_buffer() {
+        assert!(!bindings(GeneratorOptions::default()).contains("pub struct CommandBuffer"));
+
+        let bindings = bindings(GeneratorOptions {
+            command_buffer: true,
+            ..Default::default()
+        });
+        assert!(bindings.contains("pub struct CommandBuffer"));
+        assert!(bindings.contains("pub fn DrawArrays(&mut self, "));
+        // recorded with the parameters of `slices::BufferData`
+        assert!(bindings.contains("pub fn BufferData<T: Copy>(&mut self, target: types::GLenum, data: &[T], usage: types::GLenum)"));
+        assert!(bindings.contains("=> super::slices::BufferData("));
+        // returns a value
+        assert!(!bindings.contains("Command::GetIntegerv"));
+    }
+
+    #[test]
+    fn test_shadow_state() {
+        let options = GeneratorOptions {
+            shadow_state: true,
+            object_handles: true,
+            ..Default::default()
+        };
+        let bindings = bindings_of(&["UseProgram", "DeleteProgram", "DrawArrays"], options);
+        assert!(bindings.contains("pub mod shadow {"));
+        assert!(bindings.contains("Option<types::Program>,"));
+        assert!(bindings.contains("pub(super) fn UseProgram(program: types::Program) -> bool"));
+        // not in the registry
+        assert!(!bindings.contains("fn BindTexture("));
+        assert!(!bindings.contains("super::shadow::DrawArrays"));
+
+        // only a command invalidating the state
+        let options = GeneratorOptions {
+            shadow_state: true,
+            ..Default::default()
+        };
+        let bindings = bindings_of(&["BindBufferBase", "DrawArrays"], options.clone());
+        assert!(bindings.contains("{ super::shadow::BindBufferBase(); "));
+        assert!(bindings.contains("pub mod shadow {"));
+        assert!(bindings.contains("pub(super) fn BindBufferBase() {"));
+
+        // no state set or invalidated by the commands
+        assert!(!bindings_of(&["DrawArrays"], options).contains("pub mod shadow {"));
+    }
+
+    #[test]
+    fn test_track_objects() {
+        let options = GeneratorOptions {
+            track_objects: true,
+            ..Default::default()
+        };
+        let bindings = bindings_of(&["GenBuffers", "DeleteBuffers", "CreateShader", "DeleteShader", "DrawArrays"], options.clone());
+        assert!(bindings.contains("pub mod objects {"));
+        assert!(bindings.contains("super::objects::created(\"Buffer\", n, buffers as *const _)"));
+        assert!(bindings.contains("super::objects::created_one(\"Shader\", objects_ret)"));
+        assert!(bindings.contains("super::objects::deleted(\"Buffer\", n, buffers as *const _)"));
+        assert!(bindings.contains("super::objects::deleted_one(\"Shader\", shader)"));
+
+        // the `count` strings of a single program
+        let bindings = bindings_of(&["CreateShaderProgramv"], options.clone());
+        assert!(bindings.contains("super::objects::created_one(\"Program\", objects_ret)"));
+        assert!(!bindings.contains("super::objects::created(\"Program\""));
+
+        assert!(!bindings_of(&["DrawArrays"], options).contains("pub mod objects {"));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let mut options = GeneratorOptions::default();
+        options.overrides.insert("Begin".to_string(), CommandOverride::Suppress);
+        options.overrides.insert(
+            "DrawArrays".to_string(),
+            CommandOverride::Replace("pub fn DrawArrays(mode: types::GLenum, range: std::ops::Range<i32>) {}".to_string()),
+        );
+        let bindings = bindings(options);
+
+        assert!(!bindings.contains("pub fn Begin("));
+        assert!(bindings.contains("pub fn DrawArrays(mode: types::GLenum, range: std::ops::Range<i32>) {}"));
+        assert!(bindings.contains("pub unsafe fn Begin(mode: types::GLenum) -> ()"));
+        assert!(bindings.contains("pub unsafe fn DrawArrays(mode: types::GLenum, first: types::GLint, count: types::GLsizei) -> ()"));
+        let raw = &bindings[bindings.find("pub mod raw {").unwrap()..];
+        assert!(!raw[..raw.find("\n}").unwrap()].contains("fn BufferData("));
+        // both are still loaded
+        assert!(bindings.contains("[\"glBegin\", \"glBufferData\", \"glDrawArrays\", \"glGetIntegerv\"]"));
+    }
+}
@@ -4,7 +4,8 @@
 //      Proprietary and confidential                                               /
 ////////////////////////////////////////////////////////////////////////////////////
 
-use crate::registry::{Cmd, Enum, Registry};
+use crate::registry::{Binding, Cmd, DebugPrints, Enum, Registry, TypeDef, TypeKind};
+use std::collections::BTreeMap;
 use std::io;
 use Api;
 
@@ -20,6 +21,136 @@ pub trait Generator {
     where W: io::Write;
 }
 
+/// Options controlling what the generators emit, set with `RegistryBuilder::options`.
+///
+/// Build it with `..Default::default()` so that options added later don't break the build script:
+///
+/// ```
+/// # use gl_generator::{DebugPrints, GeneratorOptions};
+/// let options = GeneratorOptions {
+///     debug_prints: DebugPrints::FunctionCalls,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GeneratorOptions {
+    /// Whether the generated functions print their calls to stdout.
+    pub debug_prints:       DebugPrints,
+    /// Parameter conversions of `GlobalTypedGenerator`, tried before the built-in ones.
+    pub conversions:        Vec<TypeConversion>,
+    /// Out-parameters which `GlobalTypedGenerator` returns instead of taking them, tried before
+    ///  the built-in ones.
+    pub return_conversions: Vec<TypeConversion>,
+    /// Replaces or suppresses the functions `GlobalTypedGenerator` creates, keyed by command name
+    ///  without the API prefix, e.g. `"ShaderSource"`.
+    pub overrides:          BTreeMap<String, CommandOverride>,
+    /// Whether `GlobalTypedGenerator` uses newtypes like `Buffer` or `Program` for object names
+    ///  instead of `GLuint`.
+    pub object_handles:     bool,
+    /// What the functions of `GlobalTypedGenerator` record in the `stats` module.
+    pub call_stats:         CallStats,
+    /// Whether `GlobalTypedGenerator` creates a `CommandBuffer` recording commands on any thread,
+    ///  which are called later on the thread of the context.
+    pub command_buffer:     bool,
+    /// Whether `GlobalTypedGenerator` creates a `shadow` module tracking bindings and other state,
+    ///  so that the functions skip the calls which wouldn't change it.
+    pub shadow_state:       bool,
+    /// Whether `GlobalTypedGenerator` creates an `objects` module tracking the objects created and
+    ///  not deleted yet in builds with debug assertions, to find leaked objects.
+    pub track_objects:      bool,
+}
+
+/// The statistics recorded by the functions of `GlobalTypedGenerator`, which are read with
+///  `stats::snapshot()` and cleared with `stats::reset()`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CallStats {
+    /// No `stats` module is created.
+    #[default]
+    None,
+    /// The number of calls of each command.
+    Counts,
+    /// The number of calls of each command and the time spent in it, measured with `Instant`.
+    Timing,
+}
+
+/// Maps a C parameter type to the Rust type taken by the functions of `GlobalTypedGenerator`.
+///
+/// `code` is inserted before the call with `{param}` replaced by the parameter name and `{cmd}`
+///  by the command name. As a parameter conversion it must shadow `{param}` with a value of the C
+///  type. As a return conversion it must declare `{param}_ret` of `rust_ty`, which is returned,
+///  and shadow `{param}` with a pointer to it.
+///
+/// ```
+/// # use gl_generator::{GeneratorOptions, TypeConversion};
+/// let options = GeneratorOptions {
+///     conversions: vec![
+///         TypeConversion::new("*const __gl_imports::raw::c_void", "&[u8]", "let {param} = {param}.as_ptr() as *const _;")
+///             .command("BufferData")
+///             .param("data"),
+///     ],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeConversion {
+    /// The C type as it appears in the bindings, e.g. `"*const types::GLint"`.
+    pub c_ty:    String,
+    pub rust_ty: String,
+    pub code:    String,
+    /// Only convert parameters of this command.
+    pub command: Option<String>,
+    /// Only convert parameters with this name.
+    pub param:   Option<String>,
+}
+
+impl TypeConversion {
+    /// Converts all parameters of type `c_ty`.
+    pub fn new<C, R, S>(c_ty: C, rust_ty: R, code: S) -> TypeConversion
+    where
+        C: Into<String>,
+        R: Into<String>,
+        S: Into<String>, {
+        TypeConversion {
+            c_ty:    c_ty.into(),
+            rust_ty: rust_ty.into(),
+            code:    code.into(),
+            command: None,
+            param:   None,
+        }
+    }
+
+    /// Restricts the conversion to the command `command`, e.g. `"BufferData"`.
+    pub fn command<S: Into<String>>(mut self, command: S) -> TypeConversion {
+        self.command = Some(command.into());
+        self
+    }
+
+    /// Restricts the conversion to parameters named `param`.
+    pub fn param<S: Into<String>>(mut self, param: S) -> TypeConversion {
+        self.param = Some(param.into());
+        self
+    }
+
+    /// Whether the conversion applies to the parameter `param` of type `ty` of the command `cmd`.
+    // `Option::is_none_or` needs Rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn applies_to(&self, cmd: &str, param: &str, ty: &str) -> bool {
+        self.c_ty == ty && self.command.as_ref().map_or(true, |c| c == cmd) && self.param.as_ref().map_or(true, |p| p == param)
+    }
+}
+
+/// Changes what `GlobalTypedGenerator` creates for a single command.
+///
+/// The command is loaded either way, and its C signature stays callable as the unsafe
+///  `raw::<name>` function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandOverride {
+    /// Creates no function for the command.
+    Suppress,
+    /// Writes this source instead of the generated function.
+    Replace(String),
+}
+
 pub fn gen_struct_name(api: Api) -> &'static str {
     match api {
         Api::Gl => "Gl",
@@ -52,26 +183,59 @@ where W: io::Write {
 
 /// Generates all the type aliases for a namespace.
 ///
-/// Aliases are either `pub type = ...` or `#[repr(C)] pub struct ... { ... }` and contain all the
-/// things that we can't obtain from the XML files.
-pub fn gen_types<W>(api: Api, dest: &mut W) -> io::Result<()>
+/// The types are those of `Registry::types`. Aliases are either `pub type = ...`, `pub enum ... {}`
+/// for opaque types or `#[repr(C)] pub struct ... { ... }`. The types which the registry only has
+/// placeholders for, like the Xlib types of GLX, come from the templates of the platform.
+pub fn gen_types<W>(registry: &Registry, dest: &mut W) -> io::Result<()>
 where W: io::Write {
-    if let Api::Egl = api {
-        writeln!(dest, "{}", include_str!("templates/types/egl.rs"))?;
-        return Ok(());
-    }
+    // the converted types refer to `types::` and `__gl_imports::`, like the bindings do
+    writeln!(dest, "use super::{{__gl_imports, types}};")?;
 
-    writeln!(dest, "{}", include_str!("templates/types/gl.rs"))?;
-
-    match api {
-        Api::Glx => writeln!(dest, "{}", include_str!("templates/types/glx.rs"))?,
-        Api::Wgl => writeln!(dest, "{}", include_str!("templates/types/wgl.rs"))?,
+    match registry.api {
+        Api::Glx => writeln!(dest, "{}", include_str!("templates/types/x11.rs"))?,
+        Api::Wgl => writeln!(dest, "{}", include_str!("templates/types/windows.rs"))?,
+        Api::Egl => writeln!(dest, "{}", include_str!("templates/types/eglplatform.rs"))?,
         _ => {},
     }
 
+    for ty in &registry.types {
+        gen_type(ty, dest)?;
+    }
+
     Ok(())
 }
 
+fn gen_type<W>(ty: &TypeDef, dest: &mut W) -> io::Result<()>
+where W: io::Write {
+    let cfg = match ty.cfg {
+        Some(ref cfg) => format!("#[cfg({})] ", cfg),
+        None => String::new(),
+    };
+    let fields = |fields: &[Binding]| fields.iter().map(|f| format!("    pub {}: {},\n", f.ident, f.ty)).collect::<String>();
+
+    match ty.kind {
+        TypeKind::Alias(ref alias) => writeln!(dest, "{}pub type {} = {};", cfg, ty.ident, alias),
+        // the generic function pointers returned by `GetProcAddress`, which may be null
+        TypeKind::FnPtr { ref params, ref ret } if params.is_empty() && ret == "()" => {
+            writeln!(dest, "{cfg}pub enum {ident}_fn {{}}\n{cfg}pub type {ident} = *mut {ident}_fn;", cfg = cfg, ident = ty.ident)
+        },
+        TypeKind::FnPtr { ref params, ref ret } => writeln!(
+            dest,
+            "{}pub type {} = extern \"system\" fn({}){};",
+            cfg,
+            ty.ident,
+            params.iter().map(|p| format!("{}: {}", p.ident, p.ty)).collect::<Vec<_>>().join(", "),
+            if ret == "()" { String::new() } else { format!(" -> {}", ret) }
+        ),
+        TypeKind::Struct(ref fs) => writeln!(dest, "#[repr(C)]\n#[derive(Clone, Copy)]\n{}pub struct {} {{\n{}}}", cfg, ty.ident, fields(fs)),
+        TypeKind::Union(ref fs) => writeln!(dest, "#[repr(C)]\n#[derive(Clone, Copy)]\n{}pub union {} {{\n{}}}", cfg, ty.ident, fields(fs)),
+        TypeKind::Opaque => writeln!(dest, "{}pub enum {} {{}}", cfg, ty.ident),
+        TypeKind::Handle => writeln!(dest, "{}pub type {} = *mut __gl_imports::raw::c_void;", cfg, ty.ident),
+        // defined by the platform template, if at all
+        TypeKind::External => Ok(()),
+    }
+}
+
 /// Generates the list of Rust `Arg`s that a `Cmd` requires.
 pub fn gen_parameters(cmd: &Cmd, with_idents: bool, with_types: bool) -> Vec<String> {
     cmd.params
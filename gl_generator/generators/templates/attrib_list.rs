@@ -0,0 +1,78 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+/// A list of attribute/value pairs as taken by the `attrib_list` parameters, which is always
+///  terminated.
+///
+/// ```ignore
+/// let attribs = egl::AttribList::new().with(egl::RED_SIZE, 8).with(egl::DEPTH_SIZE, 24);
+/// let context = egl::CreateContext(display, config, egl::NO_CONTEXT, &attribs);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AttribList<T> {
+    // the pairs followed by `Attrib::NONE`
+    items: Vec<T>,
+}
+
+/// The value types of attribute lists.
+pub trait Attrib: Copy {
+    /// The attribute terminating a list.
+    const NONE: Self;
+
+    fn from_i64(value: i64) -> Self;
+}
+
+/// Values which can be attributes or values in an `AttribList`, e.g. enums and integers.
+pub trait IntoAttrib {
+    fn into_i64(self) -> i64;
+}
+
+macro_rules! impl_into_attrib {
+    ($($ty:ty),*) => {
+        $(impl IntoAttrib for $ty {
+            #[inline]
+            fn into_i64(self) -> i64 {
+                self as i64
+            }
+        })*
+    };
+}
+
+impl_into_attrib!(i8, u8, i16, u16, i32, u32, i64, isize, usize, bool);
+
+impl<T: Attrib> AttribList<T> {
+    /// Creates an empty list.
+    pub fn new() -> AttribList<T> {
+        AttribList { items: vec![T::NONE] }
+    }
+
+    /// Appends the attribute `attrib` with the value `value`.
+    pub fn with<A: IntoAttrib, V: IntoAttrib>(mut self, attrib: A, value: V) -> AttribList<T> {
+        self.push(attrib, value);
+        self
+    }
+
+    /// Appends the attribute `attrib` with the value `value`.
+    pub fn push<A: IntoAttrib, V: IntoAttrib>(&mut self, attrib: A, value: V) {
+        let end = self.items.len() - 1;
+        self.items.splice(end..end, [T::from_i64(attrib.into_i64()), T::from_i64(value.into_i64())].iter().cloned());
+    }
+
+    /// Returns the pairs and the terminator.
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.items.as_ptr()
+    }
+}
+
+impl<T: Attrib> Default for AttribList<T> {
+    fn default() -> AttribList<T> {
+        AttribList::new()
+    }
+}
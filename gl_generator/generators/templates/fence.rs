@@ -0,0 +1,102 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+/// A sync object signaled once the commands issued before it completed, which is deleted when
+///  dropped.
+///
+/// ```ignore
+/// gl::slices::BufferSubData(gl::ARRAY_BUFFER, offset, &vertices);
+/// let fence = gl::Fence::new().expect("Could not create a fence");
+/// // ...
+/// if fence.wait(Duration::from_millis(1)) == Ok(gl::FenceStatus::TimedOut) {
+///     // the upload is still in flight, use another buffer
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Fence {
+    sync: types::GLsync,
+}
+
+/// The result of `Fence::wait`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FenceStatus {
+    /// The fence was already signaled when the wait started.
+    AlreadySignaled,
+    /// The fence was signaled during the wait.
+    Signaled,
+    /// The fence was not signaled before the timeout expired.
+    TimedOut,
+}
+
+/// `ClientWaitSync` returned `WAIT_FAILED`, e.g. because the context was lost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WaitFailed;
+
+impl Fence {
+    /// Inserts a fence into the command stream, or returns `None` if `FenceSync` failed.
+    pub fn new() -> Option<Fence> {
+        // SYNC_GPU_COMMANDS_COMPLETE
+        let sync = unsafe { raw::FenceSync(0x9117, 0) };
+        match sync.is_null() {
+            true => None,
+            false => Some(Fence { sync }),
+        }
+    }
+
+    /// Takes ownership of the sync object `sync`.
+    ///
+    /// # Safety
+    ///
+    /// `sync` must be a sync object which is deleted by nothing but the returned `Fence`.
+    pub unsafe fn from_raw(sync: types::GLsync) -> Fence {
+        Fence { sync }
+    }
+
+    /// Returns the sync object, which is no longer deleted.
+    pub fn into_raw(self) -> types::GLsync {
+        let sync = self.sync;
+        __gl_imports::mem::forget(self);
+        sync
+    }
+
+    /// Blocks until the fence is signaled or `timeout` expired, flushing the commands first so that
+    ///  it can be signaled at all.
+    pub fn wait(&self, timeout: std::time::Duration) -> Result<FenceStatus, WaitFailed> {
+        let timeout = timeout.as_secs().saturating_mul(1_000_000_000).saturating_add(timeout.subsec_nanos() as u64);
+        // SYNC_FLUSH_COMMANDS_BIT
+        match unsafe { raw::ClientWaitSync(self.sync, 0x1, timeout) } {
+            // ALREADY_SIGNALED
+            0x911A => Ok(FenceStatus::AlreadySignaled),
+            // CONDITION_SATISFIED
+            0x911C => Ok(FenceStatus::Signaled),
+            // TIMEOUT_EXPIRED
+            0x911B => Ok(FenceStatus::TimedOut),
+            _ => Err(WaitFailed),
+        }
+    }
+
+    /// Makes the server wait for the fence before executing the commands issued after this,
+    ///  without blocking.
+    pub fn server_wait(&self) {
+        // TIMEOUT_IGNORED
+        unsafe { raw::WaitSync(self.sync, 0, 0xFFFF_FFFF_FFFF_FFFF) }
+    }
+
+    /// Returns whether the fence is signaled, without blocking or flushing.
+    pub fn is_signaled(&self) -> bool {
+        let mut status = 0;
+        // SYNC_STATUS
+        unsafe { raw::GetSynciv(self.sync, 0x9114, 1, std::ptr::null_mut(), &mut status) };
+        // SIGNALED
+        status == 0x9119
+    }
+}
+
+impl Drop for Fence {
+    fn drop(&mut self) {
+        unsafe { raw::DeleteSync(self.sync) }
+    }
+}
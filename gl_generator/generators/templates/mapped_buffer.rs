@@ -0,0 +1,104 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+/// A mapped range of a buffer as elements of `T`, which is unmapped when dropped.
+///
+/// Returned by `MapBuffer`, `MapBufferRange` and `MapNamedBufferRange`. Bytes at the end of the
+///  range which don't make up a whole `T` are not accessible. Dropping the mapping ignores whether
+///  the data store was corrupted while mapped, `unmap` returns it.
+///
+/// ```ignore
+/// let mut vertices = unsafe { gl::MapBufferRange::<f32>(gl::ARRAY_BUFFER, 0, size, gl::MAP_WRITE_BIT) }.unwrap();
+/// vertices.as_mut_slice().copy_from_slice(&data);
+/// vertices.unmap().expect("Vertex data corrupted");
+/// ```
+#[must_use = "the buffer is unmapped when this is dropped"]
+pub struct MappedBuffer<T> {
+    ptr:      *mut T,
+    len:      usize,
+    readable: bool,
+    writable: bool,
+    // the target or buffer passed to `unmap_fn`
+    name:     types::GLuint,
+    unmap_fn: fn(types::GLuint) -> types::GLboolean,
+}
+
+/// The data store of a buffer became corrupted while it was mapped, e.g. because the screen
+///  resolution changed, and its contents are undefined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DataCorrupted;
+
+impl<T: Copy> MappedBuffer<T> {
+    /// Wraps the result `ptr` of a mapping command of `size` bytes, or returns `None` if it is null.
+    ///
+    /// Panics if `T` is zero-sized or `ptr` is not aligned for it, after unmapping the buffer.
+    unsafe fn new(
+        ptr: *mut __gl_imports::raw::c_void,
+        size: usize,
+        readable: bool,
+        writable: bool,
+        name: types::GLuint,
+        unmap_fn: fn(types::GLuint) -> types::GLboolean,
+    ) -> Option<MappedBuffer<T>> {
+        if ptr.is_null() {
+            return None;
+        }
+
+        let elem_size = __gl_imports::mem::size_of::<T>();
+        let mapping = MappedBuffer {
+            ptr: ptr as *mut T,
+            len: size.checked_div(elem_size).unwrap_or(0),
+            readable,
+            writable,
+            name,
+            unmap_fn,
+        };
+        assert!(elem_size != 0, "Cannot map a buffer as zero-sized elements");
+        assert!(ptr as usize & (__gl_imports::mem::align_of::<T>() - 1) == 0, "The mapped range is not aligned for its elements");
+        Some(mapping)
+    }
+
+    /// The number of mapped elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The mapped elements.
+    ///
+    /// Panics if the range was not mapped for reading.
+    pub fn as_slice(&self) -> &[T] {
+        assert!(self.readable, "The buffer was not mapped for reading");
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// The mapped elements, which have undefined values if the range was not mapped for reading.
+    ///
+    /// Panics if the range was not mapped for writing.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        assert!(self.writable, "The buffer was not mapped for writing");
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+
+    /// Unmaps the buffer, returning whether its data store was corrupted while it was mapped.
+    pub fn unmap(self) -> Result<(), DataCorrupted> {
+        let unmapped = (self.unmap_fn)(self.name);
+        __gl_imports::mem::forget(self);
+        match unmapped {
+            0 => Err(DataCorrupted),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T> Drop for MappedBuffer<T> {
+    fn drop(&mut self) {
+        (self.unmap_fn)(self.name);
+    }
+}
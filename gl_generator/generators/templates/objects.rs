@@ -0,0 +1,145 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+/// The objects created by the functions and not deleted yet, with the backtraces of their creation,
+///  to find leaked objects. They are only tracked in builds with debug assertions.
+///
+/// The objects of all contexts are tracked together, call `report` before destroying a context and
+///  `clear` after it.
+///
+/// ```ignore
+/// // before destroying the context
+/// assert_eq!(gl::objects::report(), 0, "Leaked GL objects");
+/// ```
+pub mod objects {
+    #![allow(dead_code)]
+    use std::backtrace::Backtrace;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+    use std::{fmt, mem, slice};
+    use super::types;
+
+    struct Object {
+        // the number of objects created before
+        order:   u64,
+        // shared by the names created by one call
+        created: Arc<Backtrace>,
+    }
+
+    type Objects = HashMap<(&'static str, types::GLuint), Object>;
+
+    static CREATED: AtomicU64 = AtomicU64::new(0);
+
+    /// An object which was created and not deleted, returned by `live`.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct LiveObject {
+        /// The type of the object, e.g. `"Buffer"`.
+        pub kind:    &'static str,
+        pub name:    types::GLuint,
+        /// The backtrace of the call creating the object.
+        pub created: String,
+    }
+
+    impl fmt::Display for LiveObject {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            write!(fmt, "{} {} created at:\n{}", self.kind, self.name, self.created)
+        }
+    }
+
+    fn live_objects() -> MutexGuard<'static, Objects> {
+        static LIVE: OnceLock<Mutex<Objects>> = OnceLock::new();
+        LIVE.get_or_init(Default::default).lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Returns the `GLuint` of `name`, which is one or an object handle wrapping one.
+    fn raw<T: Copy>(name: T) -> types::GLuint {
+        assert_eq!(mem::size_of::<T>(), mem::size_of::<types::GLuint>());
+        unsafe { mem::transmute_copy(&name) }
+    }
+
+    /// Returns the `n` names at `names`, which are `GLuint`s or object handles wrapping them.
+    unsafe fn raw_names<'a, T>(n: types::GLsizei, names: *const T) -> &'a [types::GLuint] {
+        assert_eq!(mem::size_of::<T>(), mem::size_of::<types::GLuint>());
+        match names.is_null() {
+            true => &[],
+            false => slice::from_raw_parts(names as *const types::GLuint, n.max(0) as usize),
+        }
+    }
+
+    fn add(kind: &'static str, names: &[types::GLuint]) {
+        let created = Arc::new(Backtrace::force_capture());
+        let mut live = live_objects();
+        for &name in names.iter().filter(|&&name| name != 0) {
+            let order = CREATED.fetch_add(1, Ordering::Relaxed);
+            // a name of a destroyed context may be reused
+            live.insert((kind, name), Object { order, created: created.clone() });
+        }
+    }
+
+    fn remove(kind: &'static str, names: &[types::GLuint]) {
+        let mut live = live_objects();
+        for &name in names {
+            live.remove(&(kind, name));
+        }
+    }
+
+    pub(super) fn created_one<T: Copy>(kind: &'static str, name: T) {
+        if cfg!(debug_assertions) {
+            add(kind, &[raw(name)]);
+        }
+    }
+
+    pub(super) unsafe fn created<T>(kind: &'static str, n: types::GLsizei, names: *const T) {
+        if cfg!(debug_assertions) {
+            add(kind, raw_names(n, names));
+        }
+    }
+
+    pub(super) fn deleted_one<T: Copy>(kind: &'static str, name: T) {
+        if cfg!(debug_assertions) {
+            remove(kind, &[raw(name)]);
+        }
+    }
+
+    pub(super) unsafe fn deleted<T>(kind: &'static str, n: types::GLsizei, names: *const T) {
+        if cfg!(debug_assertions) {
+            remove(kind, raw_names(n, names));
+        }
+    }
+
+    /// Returns the objects which were created and not deleted, in the order of their creation.
+    pub fn live() -> Vec<LiveObject> {
+        let live = live_objects();
+        let mut objects = live.iter().collect::<Vec<_>>();
+        objects.sort_by_key(|(_, object)| object.order);
+        objects
+            .into_iter()
+            .map(|(&(kind, name), object)| LiveObject {
+                kind,
+                name,
+                created: object.created.to_string(),
+            })
+            .collect()
+    }
+
+    /// Prints the objects which were created and not deleted to stderr, returning their number.
+    pub fn report() -> usize {
+        let live = live();
+        if !live.is_empty() {
+            eprintln!("{} GL objects were not deleted:", live.len());
+            for object in &live {
+                eprintln!("{}", object);
+            }
+        }
+        live.len()
+    }
+
+    /// Forgets the tracked objects, e.g. after destroying the context which had them.
+    pub fn clear() {
+        live_objects().clear();
+    }
+}
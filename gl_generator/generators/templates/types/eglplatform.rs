@@ -0,0 +1,17 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2019 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+// The platform types, which the EGL registry only has placeholders for
+// IMPORTANT: these are aliases to the same level of the bindings
+// the values must be defined by the user
+pub type EGLNativeDisplayType = super::EGLNativeDisplayType;
+pub type EGLNativePixmapType = super::EGLNativePixmapType;
+pub type EGLNativeWindowType = super::EGLNativeWindowType;
+pub type NativeDisplayType = super::NativeDisplayType;
+pub type NativePixmapType = super::NativePixmapType;
+pub type NativeWindowType = super::NativeWindowType;
+
+pub type Bool = EGLBoolean;
@@ -0,0 +1,34 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2019 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+// The Xlib types, which the GLX registry only has placeholders for
+
+pub type XID = super::__gl_imports::raw::c_ulong;
+pub type Bool = super::__gl_imports::raw::c_int; // Not sure if this is correct...
+pub type Status = super::__gl_imports::raw::c_int;
+pub enum Display {}
+pub enum Screen {}
+
+pub type Colormap = XID;
+pub type Font = XID;
+pub type Pixmap = XID;
+pub enum Visual {}
+pub type VisualID = super::__gl_imports::raw::c_ulong;
+pub type Window = XID;
+
+#[repr(C)]
+pub struct XVisualInfo {
+    pub visual:        *mut Visual,
+    pub visualid:      VisualID,
+    pub screen:        super::__gl_imports::raw::c_int,
+    pub depth:         super::__gl_imports::raw::c_int,
+    pub class:         super::__gl_imports::raw::c_int,
+    pub red_mask:      super::__gl_imports::raw::c_ulong,
+    pub green_mask:    super::__gl_imports::raw::c_ulong,
+    pub blue_mask:     super::__gl_imports::raw::c_ulong,
+    pub colormap_size: super::__gl_imports::raw::c_int,
+    pub bits_per_rgb:  super::__gl_imports::raw::c_int,
+}
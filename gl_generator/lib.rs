@@ -14,7 +14,7 @@
 //! ```no_run
 //! extern crate gl_generator;
 //!
-//! use gl_generator::{Registry, Api, Profile, Fallbacks, GlobalGenerator};
+//! use gl_generator::{Api, GlobalTypedGenerator, Profile, RegistryBuilder};
 //! use std::env;
 //! use std::fs::File;
 //! use std::path::Path;
@@ -23,8 +23,12 @@
 //!     let dest = env::var("OUT_DIR").unwrap();
 //!     let mut file = File::create(&Path::new(&dest).join("gl_bindings.rs")).unwrap();
 //!
-//!     Registry::new(Api::Gl, (4, 5), Profile::Core, Fallbacks::All, [])
-//!         .write_bindings(GlobalGenerator, &mut file)
+//!     RegistryBuilder::new(Api::Gl, (4, 5))
+//!         .profile(Profile::Core)
+//!         .extensions(&["GL_ARB_draw_buffers_blend"])
+//!         .build()
+//!         .unwrap()
+//!         .write_bindings(GlobalTypedGenerator, &mut file)
 //!         .unwrap();
 //! }
 //! ```
@@ -40,13 +44,9 @@
 //! When you generate bindings for EGL, the following platform-specific types must be declared
 //!  *at the same level where you include the bindings*:
 //!
-//! - `khronos_utime_nanoseconds_t`
-//! - `khronos_uint64_t`
-//! - `khronos_ssize_t`
 //! - `EGLNativeDisplayType`
 //! - `EGLNativePixmapType`
 //! - `EGLNativeWindowType`
-//! - `EGLint`
 //! - `NativeDisplayType`
 //! - `NativePixmapType`
 //! - `NativeWindowType`
@@ -55,6 +55,11 @@
 extern crate lazy_static;
 #[macro_use]
 extern crate log;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
 extern crate xml;
 
 #[cfg(feature = "unstable_generator_utils")]
@@ -64,6 +69,6 @@ mod generators;
 
 mod registry;
 
-pub use generators::{debug_struct_gen::DebugStructGenerator, global_typed_gen::GlobalTypedGenerator, Generator};
+pub use generators::{debug_struct_gen::DebugStructGenerator, global_typed_gen::GlobalTypedGenerator, CallStats, CommandOverride, Generator, GeneratorOptions, TypeConversion};
 
 pub use registry::*;
@@ -0,0 +1,227 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+use std::{collections::BTreeSet, error, fmt};
+
+use generators::{CallStats, GeneratorOptions};
+use registry::{parse, Api, DebugPrints, Fallbacks, Profile, Registry};
+
+/// Builds a `Registry` from named settings, validating them against the registry XML.
+///
+/// ```no_run
+/// # use gl_generator::{Api, DebugPrints, Profile, RegistryBuilder};
+/// let registry = RegistryBuilder::new(Api::Gl, (4, 6))
+///     .profile(Profile::Core)
+///     .extensions(&["GL_EXT_texture_filter_anisotropic", "GL_ARB_draw_buffers_blend"])
+///     .debug_prints(DebugPrints::FunctionCalls)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct RegistryBuilder {
+    api:        Api,
+    version:    (u8, u8),
+    profile:    Option<Profile>,
+    fallbacks:  Fallbacks,
+    extensions: Vec<String>,
+    only:       Option<BTreeSet<String>>,
+    options:    GeneratorOptions,
+}
+
+impl RegistryBuilder {
+    /// Starts a registry of version `version` of the API `api`, e.g. `(Api::Gl, (4, 6))`.
+    ///
+    /// By default fallbacks are enabled, no extensions are requested, `GeneratorOptions::default()`
+    ///  is used and, for `Api::Gl`, the core profile.
+    pub fn new(api: Api, version: (u8, u8)) -> RegistryBuilder {
+        RegistryBuilder {
+            api,
+            version,
+            profile: None,
+            fallbacks: Fallbacks::All,
+            extensions: Vec::new(),
+            only: None,
+            options: GeneratorOptions::default(),
+        }
+    }
+
+    /// Sets the profile. Only `Api::Gl` has profiles.
+    pub fn profile(mut self, profile: Profile) -> RegistryBuilder {
+        self.profile = Some(profile);
+        self
+    }
+
+    pub fn fallbacks(mut self, fallbacks: Fallbacks) -> RegistryBuilder {
+        self.fallbacks = fallbacks;
+        self
+    }
+
+    /// Requests an extension, e.g. `"GL_ARB_draw_buffers_blend"`.
+    pub fn extension<S: Into<String>>(mut self, extension: S) -> RegistryBuilder {
+        self.extensions.push(extension.into());
+        self
+    }
+
+    /// Requests several extensions, see `extension`.
+    pub fn extensions<I>(mut self, extensions: I) -> RegistryBuilder
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>, {
+        self.extensions.extend(extensions.into_iter().map(|e| e.as_ref().to_string()));
+        self
+    }
+
+    /// Restricts the bindings to the commands named in `idents` and the enums they need, see
+    ///  `Registry::retain_only`. The identifiers are those of the bindings, e.g. `Clear`, and can
+    ///  come from `used_idents` or `read_allowlist`.
+    ///
+    /// ```no_run
+    /// # use gl_generator::{used_idents, Api, RegistryBuilder};
+    /// let registry = RegistryBuilder::new(Api::Gl, (4, 6))
+    ///     .only(used_idents("../app/src", "gl").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn only<I>(mut self, idents: I) -> RegistryBuilder
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>, {
+        self.only.get_or_insert_with(BTreeSet::new).extend(idents.into_iter().map(|i| i.as_ref().to_string()));
+        self
+    }
+
+    /// Sets all the options passed on to the generators.
+    pub fn options(mut self, options: GeneratorOptions) -> RegistryBuilder {
+        self.options = options;
+        self
+    }
+
+    /// Sets `GeneratorOptions::debug_prints`.
+    pub fn debug_prints(mut self, debug_prints: DebugPrints) -> RegistryBuilder {
+        self.options.debug_prints = debug_prints;
+        self
+    }
+
+    /// Sets `GeneratorOptions::call_stats`.
+    pub fn call_stats(mut self, call_stats: CallStats) -> RegistryBuilder {
+        self.options.call_stats = call_stats;
+        self
+    }
+
+    /// Parses the registry, or loads it from the cache with the `serde` feature.
+    pub fn build(self) -> Result<Registry, RegistryError> {
+        let profile = match (self.profile, self.api) {
+            (Some(profile), Api::Gl) => profile,
+            (Some(_), api) => return Err(RegistryError::ProfileNotSupported { api }),
+            (None, _) => Profile::Core,
+        };
+
+        let (major, minor) = self.version;
+        let filter = parse::Filter {
+            api: self.api,
+            fallbacks: self.fallbacks,
+            extensions: self.extensions.iter().cloned().collect(),
+            version: format!("{}.{}", major, minor),
+            profile,
+        };
+
+        #[cfg(feature = "serde")]
+        let mut registry = match super::cache::dir() {
+            Some(dir) => super::cache::load_or_parse(&dir, &filter, &super::sources(&filter), || super::from_sources(&filter))?,
+            None => super::from_sources(&filter)?,
+        };
+        #[cfg(not(feature = "serde"))]
+        let mut registry = super::from_sources(&filter)?;
+
+        // the registry keeps every extension supporting the API, so anything else is unknown
+        if let Some(extension) = filter.extensions.iter().find(|&name| registry.extensions.iter().all(|e| e.name != *name)) {
+            return Err(RegistryError::UnknownExtension {
+                extension: extension.clone(),
+            });
+        }
+
+        if let Some(ref only) = self.only {
+            registry.retain_only(only);
+        }
+        registry.options = self.options;
+        Ok(registry)
+    }
+}
+
+/// An error in the settings of a `RegistryBuilder`, or in the registry XML.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    /// A profile was set for an API other than `Api::Gl`.
+    ProfileNotSupported { api: Api },
+    /// The registry has no feature of the requested version.
+    UnknownVersion { api: Api, version: String },
+    /// The requested extension is not part of the registry.
+    UnknownExtension { extension: String },
+    /// The requested extension exists but is not defined for the API.
+    UnsupportedExtension { extension: String, api: Api },
+    /// The registry XML has an element or text where it was not expected.
+    UnexpectedXml { expected: String, found: String },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RegistryError::ProfileNotSupported { api } => write!(fmt, "The {} API has no profiles", api),
+            RegistryError::UnknownVersion { api, ref version } => write!(fmt, "Did not find {} version {} in the registry", api, version),
+            RegistryError::UnknownExtension { ref extension } => write!(fmt, "Did not find {} in the registry", extension),
+            RegistryError::UnsupportedExtension { ref extension, api } => write!(fmt, "Requested {}, which doesn't support the {} API", extension, api),
+            RegistryError::UnexpectedXml { ref expected, ref found } => write!(fmt, "Expected {} in the registry, found: {}", expected, found),
+        }
+    }
+}
+
+impl error::Error for RegistryError {}
+
+#[cfg(test)]
+mod tests {
+    use {Api, Profile, RegistryBuilder, RegistryError};
+
+    #[test]
+    fn test_profile_not_supported() {
+        let err = RegistryBuilder::new(Api::Gles2, (3, 2)).profile(Profile::Core).build().unwrap_err();
+        assert_eq!(err, RegistryError::ProfileNotSupported { api: Api::Gles2 });
+    }
+
+    #[test]
+    fn test_unknown_version() {
+        let err = RegistryBuilder::new(Api::Gl, (4, 9)).build().unwrap_err();
+        assert_eq!(
+            err,
+            RegistryError::UnknownVersion {
+                api:     Api::Gl,
+                version: "4.9".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_extensions() {
+        let err = RegistryBuilder::new(Api::Gl, (3, 3)).extension("GL_ARB_no_such_extension").build().unwrap_err();
+        assert_eq!(
+            err,
+            RegistryError::UnknownExtension {
+                extension: "GL_ARB_no_such_extension".to_string(),
+            }
+        );
+
+        let err = RegistryBuilder::new(Api::Gles2, (2, 0)).extension("GL_ARB_draw_buffers_blend").build().unwrap_err();
+        assert_eq!(
+            err,
+            RegistryError::UnsupportedExtension {
+                extension: "GL_ARB_draw_buffers_blend".to_string(),
+                api:       Api::Gles2,
+            }
+        );
+
+        let registry = RegistryBuilder::new(Api::Gl, (3, 3)).extensions(&["GL_ARB_draw_buffers_blend"]).build().unwrap();
+        assert!(registry.cmds.iter().any(|c| c.proto.ident == "BlendFunciARB"));
+    }
+}
@@ -0,0 +1,125 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+//! Caches parsed registries as JSON files, so build scripts don't have to parse the registry XML
+//!  on every build.
+//!
+//! The cache directory is `GL_GENERATOR_CACHE_DIR` if set, otherwise the `OUT_DIR` of the build
+//!  script. Pointing `GL_GENERATOR_CACHE_DIR` at a shared directory lets clean builds and several
+//!  crates reuse the same parse.
+
+use std::{collections::hash_map::DefaultHasher,
+          env,
+          fs,
+          hash::{Hash, Hasher},
+          path::{Path, PathBuf},
+          process};
+
+use registry::{parse::{Filter, PARSER_VERSION}, Registry, RegistryError, JSON_SCHEMA_VERSION};
+
+pub const CACHE_DIR_VAR: &str = "GL_GENERATOR_CACHE_DIR";
+
+pub fn dir() -> Option<PathBuf> {
+    env::var_os(CACHE_DIR_VAR).or_else(|| env::var_os("OUT_DIR")).map(PathBuf::from)
+}
+
+/// Returns the file name under which the registry for `filter` is cached.
+///
+/// The key covers everything the parse result depends on: the filter, the registry XML sources
+///  (and with them the `khronos_api` version), the version of this crate, of the parser output and
+///  of the JSON schema.
+fn file_name(filter: &Filter, sources: &[&[u8]]) -> String {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    PARSER_VERSION.hash(&mut hasher);
+    JSON_SCHEMA_VERSION.hash(&mut hasher);
+    sources.hash(&mut hasher);
+    filter.api.hash(&mut hasher);
+    filter.fallbacks.hash(&mut hasher);
+    filter.extensions.hash(&mut hasher);
+    filter.profile.hash(&mut hasher);
+    filter.version.hash(&mut hasher);
+
+    format!("gl_registry-{}-{}-{:016x}.json", filter.api, filter.version, hasher.finish())
+}
+
+/// Loads the registry for `filter` from the cache in `dir`, or parses it with `parse` and stores
+///  it there.
+///
+/// A missing, unreadable or outdated cache file is never an error, the registry is simply parsed
+///  again. Registries which fail to parse are not cached.
+pub fn load_or_parse<F>(dir: &Path, filter: &Filter, sources: &[&[u8]], parse: F) -> Result<Registry, RegistryError>
+where F: FnOnce() -> Result<Registry, RegistryError> {
+    let path = dir.join(file_name(filter, sources));
+
+    if let Ok(file) = fs::File::open(&path) {
+        match Registry::from_json(file) {
+            Ok(registry) => {
+                debug!("Loaded cached registry {}", path.display());
+                return Ok(registry);
+            },
+            Err(err) => warn!("Ignoring cached registry {}: {}", path.display(), err),
+        }
+    }
+
+    let registry = parse()?;
+
+    // write to a temporary file first, so concurrent builds never read a partially written cache
+    let tmp_path = path.with_extension(format!("{}.tmp", process::id()));
+    let written = fs::create_dir_all(dir)
+        .and_then(|()| fs::File::create(&tmp_path))
+        .and_then(|mut file| registry.write_json(&mut file))
+        .and_then(|()| fs::rename(&tmp_path, &path));
+    if let Err(err) = written {
+        warn!("Could not cache registry in {}: {}", path.display(), err);
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    Ok(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use registry::{cache, parse::Filter, tests::registry};
+    use {Api, Fallbacks, Profile};
+
+    fn filter(version: &str) -> Filter {
+        Filter {
+            api: Api::Gl,
+            fallbacks: Fallbacks::All,
+            extensions: Default::default(),
+            profile: Profile::Core,
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_load_or_parse() {
+        let dir = env::temp_dir().join(format!("gl_generator_cache_test_{}", process::id()));
+        let sources: &[&[u8]] = &[b"<registry/>"];
+
+        let parsed = cache::load_or_parse(&dir, &filter("3.2"), sources, || Ok(registry("3.2", Profile::Core))).unwrap();
+        let cached = cache::load_or_parse(&dir, &filter("3.2"), sources, || panic!("not cached")).unwrap();
+        assert_eq!(cached, parsed);
+
+        // a different filter or source must not hit the cache
+        let mut parsed_again = false;
+        cache::load_or_parse(&dir, &filter("1.1"), sources, || {
+            parsed_again = true;
+            Ok(registry("1.1", Profile::Core))
+        })
+        .unwrap();
+        assert!(parsed_again);
+        assert_ne!(
+            cache::file_name(&filter("3.2"), sources),
+            cache::file_name(&filter("3.2"), &[b"<registry></registry>"])
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
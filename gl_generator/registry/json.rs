@@ -0,0 +1,89 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+use serde_json::{self, Value};
+use std::io;
+
+use registry::Registry;
+
+/// The version of the JSON schema written by `Registry::write_json`.
+///
+/// It is bumped whenever a field is renamed, removed or changes its meaning. Adding a field does
+///  not bump it, so readers should ignore fields they don't know.
+pub const JSON_SCHEMA_VERSION: u64 = 3;
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    schema_version: u64,
+    registry:       &'a Registry,
+}
+
+impl Registry {
+    /// Writes the registry as a JSON object of the form
+    ///  `{"schema_version": 3, "registry": {"api": "gl", "enums": [...], "cmds": [...], ...}}`.
+    ///
+    /// The field names are those of `Registry` and the types it contains. Sets and maps are
+    ///  written in sorted order, so the output only changes when the registry does.
+    pub fn write_json<W>(&self, dest: &mut W) -> io::Result<()>
+    where W: io::Write {
+        let envelope = Envelope {
+            schema_version: JSON_SCHEMA_VERSION,
+            registry:       self,
+        };
+        serde_json::to_writer(dest, &envelope).map_err(io::Error::from)
+    }
+
+    /// Reads a registry written by `write_json`.
+    ///
+    /// Fails with `io::ErrorKind::InvalidData` if the JSON is malformed or was written with a
+    ///  different `JSON_SCHEMA_VERSION`.
+    pub fn from_json<R>(src: R) -> io::Result<Registry>
+    where R: io::Read {
+        let mut envelope: Value = serde_json::from_reader(src)?;
+        match envelope.get("schema_version").and_then(Value::as_u64) {
+            Some(JSON_SCHEMA_VERSION) => (),
+            Some(version) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unsupported registry schema version {}, expected {}", version, JSON_SCHEMA_VERSION),
+                ))
+            },
+            None => return Err(io::Error::new(io::ErrorKind::InvalidData, "missing registry schema version")),
+        }
+        Ok(serde_json::from_value(envelope["registry"].take())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use registry::tests::registry;
+    use {Profile, Registry};
+
+    #[test]
+    fn test_round_trip() {
+        let registry = registry("3.2", Profile::Compatibility);
+        let mut json = Vec::new();
+        registry.write_json(&mut json).unwrap();
+        assert_eq!(Registry::from_json(&json[..]).unwrap(), registry);
+    }
+
+    #[test]
+    fn test_schema() {
+        let mut json = Vec::new();
+        registry("1.1", Profile::Core).write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.starts_with(r#"{"schema_version":3,"registry":{"api":"gl","#));
+        assert!(json.contains(r#"{"ident":"mode","ty":"types::GLenum","group":"PrimitiveType","len":null}"#));
+        assert!(json.contains(r#""features":[{"api":"gl","name":"GL_VERSION_1_0","number":"1.0","#));
+        assert!(!json.contains("debug_prints"));
+    }
+
+    #[test]
+    fn test_wrong_schema_version() {
+        let json = r#"{"schema_version":0,"registry":{}}"#;
+        assert!(Registry::from_json(json.as_bytes()).is_err());
+    }
+}
@@ -11,13 +11,28 @@ use std::{borrow::Cow,
           fmt,
           hash::{Hash, Hasher},
           io,
-          ops::{Add, AddAssign}};
+          ops::{Add, AddAssign, Sub, SubAssign},
+          str::FromStr};
 
+use generators::GeneratorOptions;
 use Generator;
 
+mod builder;
+#[cfg(feature = "serde")]
+mod cache;
+#[cfg(feature = "serde")]
+mod json;
 mod parse;
+mod usage;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub use self::builder::{RegistryBuilder, RegistryError};
+pub use self::usage::{read_allowlist, used_idents};
+#[cfg(feature = "serde")]
+pub use self::json::JSON_SCHEMA_VERSION;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Api {
     Gl,
     Glx,
@@ -44,25 +59,48 @@ impl fmt::Display for Api {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+impl FromStr for Api {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Api, String> {
+        match parse::api_from_str(src) {
+            Ok(Some(api)) => Ok(api),
+            _ => Err(format!("unknown API `{}`", src)),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum DebugPrints {
     FunctionCalls,
+    #[default]
     None,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Fallbacks {
     All,
     None,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Profile {
     Core,
     Compatibility,
 }
 
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Profile, String> {
+        parse::profile_from_str(src).map_err(|()| format!("unknown profile `{}`", src))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Enum {
     pub ident: String,
     pub value: String,
@@ -78,13 +116,17 @@ impl Hash for Enum {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Binding {
     pub ident: String,
     pub ty:    Cow<'static, str>,
     pub group: Option<String>,
+    /// The length of the pointed-to array as a C expression, e.g. `n`, `count*4` or `COMPSIZE(pname)`.
+    pub len:   Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Group {
     pub ident:      String,
     pub enums_type: Option<String>,
@@ -92,10 +134,13 @@ pub struct Group {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Cmd {
     pub proto:    Binding,
     pub params:   Vec<Binding>,
     pub alias:    Option<String>,
+    /// The command taking the scalar parameters of this one as an array, e.g. `Color3fv` for
+    ///  `Color3f`.
     pub vecequiv: Option<String>,
     pub glx:      Option<GlxOpcode>,
 }
@@ -107,51 +152,129 @@ impl Hash for Cmd {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GlxOpcode {
     pub opcode: String,
     pub name:   Option<String>,
 }
 
+/// A type of the `<types>` section of the registry, with its C declaration converted to Rust.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TypeDef {
+    pub ident: String,
+    /// The `cfg` predicate the definition is limited to, for types which differ by platform.
+    pub cfg:   Option<String>,
+    pub kind:  TypeKind,
+}
+
+/// The definition of a `TypeDef`. Types are Rust types, like `Binding::ty`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum TypeKind {
+    /// `typedef T name;`
+    Alias(String),
+    /// `typedef R (*name)(params);`, with `()` as the return type of `void` functions.
+    FnPtr { params: Vec<Binding>, ret: String },
+    /// `struct name { fields };` or `typedef struct { fields } name;`
+    Struct(Vec<Binding>),
+    /// `typedef union { fields } name;`
+    Union(Vec<Binding>),
+    /// `struct name;`, a type only used behind pointers.
+    Opaque,
+    /// `DECLARE_HANDLE(name)`, a pointer to an opaque type.
+    Handle,
+    /// A placeholder for a type defined outside of the registry, e.g. by Xlib or the platform.
+    External,
+}
+
+/// A `<feature>` (core API version) of the registry, e.g. `GL_VERSION_4_5`.
+///
+/// Enum and command names are stored the same way as `Enum::ident` and `Cmd::proto.ident`, with
+///  the API prefix trimmed.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Feature {
+    pub api:      Api,
+    pub name:     String,
+    pub number:   String,
+    pub requires: Vec<Require>,
+    pub removes:  Vec<Remove>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Require {
+    /// A reference to the earlier types, by name
+    pub enums:    Vec<String>,
+    /// A reference to the earlier types, by name
+    pub commands: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Remove {
+    // always Core, for now
+    pub profile:  Profile,
+    /// A reference to the earlier types, by name
+    pub enums:    Vec<String>,
+    /// A reference to the earlier types, by name
+    pub commands: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Extension {
+    pub name:      String,
+    /// which apis this extension is defined for (see Feature.api)
+    pub supported: Vec<Api>,
+    pub requires:  Vec<Require>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Registry {
     pub api:          Api,
     pub enums:        BTreeSet<Enum>,
     pub cmds:         BTreeSet<Cmd>,
     pub aliases:      BTreeMap<String, Vec<String>>,
     pub groups:       BTreeMap<String, Group>,
-    pub debug_prints: DebugPrints,
+    /// The types of the API in registry order. Types defined by several registries, like the GL
+    ///  types used by GLX, are taken from the one defining them.
+    pub types:        Vec<TypeDef>,
+    /// The features up to the requested version, in registry order.
+    pub features:     Vec<Feature>,
+    /// All extensions supporting the requested API, whether requested or not.
+    pub extensions:   Vec<Extension>,
+    /// The options passed on to the generators, see `RegistryBuilder::options`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub options:      GeneratorOptions,
 }
 
+/// The commands called by the functions and types `GlobalTypedGenerator` creates for an
+///  identifier, besides the command itself.
+pub(crate) const CALLED_CMDS: &[(&str, &[&str])] = &[
+    ("MapBuffer", &["UnmapBuffer", "GetBufferParameteri64v"]),
+    ("MapBufferRange", &["UnmapBuffer"]),
+    ("MapNamedBufferRange", &["UnmapNamedBuffer"]),
+    ("Fence", &["FenceSync", "ClientWaitSync", "WaitSync", "GetSynciv", "DeleteSync"]),
+];
+
 impl Registry {
+    /// Shorthand for a `RegistryBuilder` with all settings given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the settings are invalid, see `RegistryError`.
     pub fn new<'a, Exts>(api: Api, version: (u8, u8), profile: Profile, fallbacks: Fallbacks, extensions: Exts, debug_prints: DebugPrints) -> Registry
     where Exts: AsRef<[&'a str]> {
-        let (major, minor) = version;
-        let extensions = extensions.as_ref().iter().map(<&str>::to_string).collect();
-
-        let filter = parse::Filter {
-            api,
-            fallbacks,
-            extensions,
-            version: format!("{}.{}", major, minor),
-            profile,
-            debug_prints,
-        };
-
-        let src = match api {
-            Api::Gl | Api::GlCore | Api::Gles1 | Api::Gles2 | Api::Glsc2 => khronos_api::GL_XML,
-            Api::Glx => khronos_api::GLX_XML,
-            Api::Wgl => khronos_api::WGL_XML,
-            Api::Egl => khronos_api::EGL_XML,
-        };
-
-        let mut registry = parse::from_xml(src, &filter, true);
-        if filter.extensions.iter().any(|e| e.starts_with("GL_ANGLE_")) {
-            registry += parse::from_xml(khronos_api::GL_ANGLE_EXT_XML, &filter, false);
-        }
-        if filter.extensions.iter().any(|e| e.starts_with("EGL_ANGLE_")) {
-            registry += parse::from_xml(khronos_api::EGL_ANGLE_EXT_XML, &filter, false);
+        let mut builder = RegistryBuilder::new(api, version).fallbacks(fallbacks).extensions(extensions.as_ref()).debug_prints(debug_prints);
+        // only the GL API has profiles
+        if api == Api::Gl {
+            builder = builder.profile(profile);
         }
-        registry
+        builder.build().unwrap_or_else(|err| panic!("{}", err))
     }
 
     pub fn write_bindings<W, G>(&self, generator: G, output: &mut W) -> io::Result<()>
@@ -173,6 +296,117 @@ impl Registry {
         }
         tys
     }
+
+    /// Compares this registry against `other`, e.g. a 4.6 core registry against a 4.1 core one.
+    ///
+    /// Enums and commands are "added" if only `other` contains them and "removed" if only `self`
+    ///  does.
+    pub fn diff(&self, other: &Registry) -> RegistryDiff {
+        let added = other.clone() - self.clone();
+        let removed = self.clone() - other.clone();
+        RegistryDiff {
+            added_enums:   added.enums.into_iter().map(|e| e.ident).collect(),
+            removed_enums: removed.enums.into_iter().map(|e| e.ident).collect(),
+            added_cmds:    added.cmds.into_iter().map(|c| c.proto.ident).collect(),
+            removed_cmds:  removed.cmds.into_iter().map(|c| c.proto.ident).collect(),
+        }
+    }
+
+    /// Returns the commands required by the feature called `feature` (e.g. `"GL_VERSION_4_5"`).
+    ///
+    /// Commands removed by a later feature are still listed.
+    pub fn commands_in_feature(&self, feature: &str) -> BTreeSet<&str> {
+        self.features
+            .iter()
+            .filter(|f| f.name == feature)
+            .flat_map(|f| f.requires.iter())
+            .flat_map(|require| require.commands.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Returns the first feature which requires the command `cmd`.
+    pub fn introduced_in(&self, cmd: &str) -> Option<&Feature> {
+        self.features
+            .iter()
+            .find(|f| f.requires.iter().any(|require| require.commands.iter().any(|c| c == cmd)))
+    }
+
+    /// Returns the enums of the registry which are members of the group `group`.
+    pub fn enums_in_group(&self, group: &str) -> Vec<&Enum> {
+        match self.groups.get(group) {
+            Some(group) => self.enums.iter().filter(|e| group.enums.contains(&e.ident)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns all extensions which require the command `cmd`.
+    pub fn extensions_providing(&self, cmd: &str) -> Vec<&Extension> {
+        self.extensions
+            .iter()
+            .filter(|ext| ext.requires.iter().any(|require| require.commands.iter().any(|c| c == cmd)))
+            .collect()
+    }
+
+    /// Returns the feature which removes the command `cmd` from the core profile.
+    pub fn removed_in_core(&self, cmd: &str) -> Option<&Feature> {
+        self.features.iter().find(|f| {
+            f.removes
+                .iter()
+                .any(|remove| remove.profile == Profile::Core && remove.commands.iter().any(|c| c == cmd))
+        })
+    }
+
+    /// Keeps only the commands named in `idents`, without their prefix, and the enums which are
+    ///  named in `idents` or are members of the groups of the kept commands' parameters. The
+    ///  commands which the generated functions and types call are kept too, like `UnmapBuffer` for
+    ///  `MapBufferRange` or `DeleteSync` for `Fence`, and the vector equivalents of the kept commands,
+    ///  like `Uniform3fv` for `Uniform3f`, which decide the form of the `arrays` functions.
+    ///
+    /// Identifiers which are neither commands nor enums are ignored.
+    pub fn retain_only(&mut self, idents: &BTreeSet<String>) {
+        let called = CALLED_CMDS.iter().filter(|(cmd, _)| idents.contains(*cmd)).flat_map(|(_, called)| called.iter());
+        let cmds = idents.iter().map(|ident| &**ident).chain(called.cloned()).collect::<BTreeSet<_>>();
+        let vectors = self
+            .cmds
+            .iter()
+            .filter(|cmd| cmds.contains(&*cmd.proto.ident))
+            .map(|cmd| cmd.vecequiv.clone().unwrap_or_else(|| format!("{}v", cmd.proto.ident)))
+            .collect::<BTreeSet<_>>();
+        self.cmds.retain(|cmd| cmds.contains(&*cmd.proto.ident) || vectors.contains(&cmd.proto.ident));
+        let kept = self.cmds.iter().map(|cmd| &*cmd.proto.ident).collect::<BTreeSet<_>>();
+        self.aliases.retain(|ident, _| kept.contains(&**ident));
+
+        let groups = self.cmds.iter().flat_map(|cmd| cmd.params.iter()).filter_map(|param| param.group.as_ref());
+        let grouped = groups.filter_map(|group| self.groups.get(group)).flat_map(|group| group.enums.iter().cloned()).collect::<BTreeSet<_>>();
+        self.enums.retain(|e| idents.contains(&e.ident) || grouped.contains(&e.ident));
+    }
+}
+
+/// Returns the registry XML files needed for `filter`, the main registry of its API first.
+fn sources(filter: &parse::Filter) -> Vec<&'static [u8]> {
+    let mut sources = match filter.api {
+        Api::Gl | Api::GlCore | Api::Gles1 | Api::Gles2 | Api::Glsc2 => vec![khronos_api::GL_XML],
+        // the GLX and WGL registries only have placeholders for the GL types
+        Api::Glx => vec![khronos_api::GLX_XML, khronos_api::GL_XML],
+        Api::Wgl => vec![khronos_api::WGL_XML, khronos_api::GL_XML],
+        Api::Egl => vec![khronos_api::EGL_XML],
+    };
+    if filter.extensions.iter().any(|e| e.starts_with("GL_ANGLE_")) {
+        sources.push(khronos_api::GL_ANGLE_EXT_XML);
+    }
+    if filter.extensions.iter().any(|e| e.starts_with("EGL_ANGLE_")) {
+        sources.push(khronos_api::EGL_ANGLE_EXT_XML);
+    }
+    sources
+}
+
+fn from_sources(filter: &parse::Filter) -> Result<Registry, RegistryError> {
+    let sources = sources(filter);
+    let mut registry = parse::from_xml(sources[0], filter, true)?;
+    for src in &sources[1..] {
+        registry += parse::from_xml(*src, filter, false)?;
+    }
+    Ok(registry)
 }
 
 impl Add for Registry {
@@ -189,5 +423,344 @@ impl AddAssign for Registry {
         self.enums.extend(other.enums);
         self.cmds.extend(other.cmds);
         self.aliases.extend(other.aliases);
+        for ty in other.types {
+            match self.types.iter().position(|t| t.ident == ty.ident) {
+                Some(i) if self.types[i].kind == TypeKind::External => self.types[i] = ty,
+                Some(_) => (),
+                None => self.types.push(ty),
+            }
+        }
+        self.features.extend(other.features);
+        self.extensions.extend(other.extensions);
+    }
+}
+
+impl Sub for Registry {
+    type Output = Registry;
+
+    fn sub(mut self, other: Registry) -> Registry {
+        self -= other;
+        self
+    }
+}
+
+/// Removes every enum and command which `other` also contains, matching them by identifier.
+impl SubAssign for Registry {
+    fn sub_assign(&mut self, other: Self) {
+        let enums = other.enums.iter().map(|e| &*e.ident).collect::<BTreeSet<_>>();
+        let cmds = other.cmds.iter().map(|c| &*c.proto.ident).collect::<BTreeSet<_>>();
+        self.enums.retain(|e| !enums.contains(&*e.ident));
+        self.cmds.retain(|c| !cmds.contains(&*c.proto.ident));
+    }
+}
+
+/// The enums and commands which differ between two registries, see `Registry::diff`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegistryDiff {
+    pub added_enums:   BTreeSet<String>,
+    pub removed_enums: BTreeSet<String>,
+    pub added_cmds:    BTreeSet<String>,
+    pub removed_cmds:  BTreeSet<String>,
+}
+
+impl RegistryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_enums.is_empty() && self.removed_enums.is_empty() && self.added_cmds.is_empty() && self.removed_cmds.is_empty()
+    }
+}
+
+impl fmt::Display for RegistryDiff {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let sections = [
+            ("Added commands", &self.added_cmds),
+            ("Removed commands", &self.removed_cmds),
+            ("Added enums", &self.added_enums),
+            ("Removed enums", &self.removed_enums),
+        ];
+        for &(title, idents) in sections.iter().filter(|&&(_, idents)| !idents.is_empty()) {
+            writeln!(fmt, "{} ({}):", title, idents.len())?;
+            for ident in idents {
+                writeln!(fmt, "    {}", ident)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use registry::parse;
+    use {Api, Fallbacks, Profile, Registry, RegistryBuilder, TypeKind};
+
+    const XML: &str = r#"<registry>
+    <types>
+        <type name="khrplatform">#include &lt;KHR/khrplatform.h&gt;</type>
+        <type>typedef unsigned int <name>GLenum</name>;</type>
+        <type>typedef int <name>GLint</name>;</type>
+        <type>typedef int <name>GLsizei</name>;</type>
+        <type>typedef khronos_ssize_t <name>GLsizeiptr</name>;</type>
+        <type>typedef char <name>GLchar</name>;</type>
+        <type api="gles1">typedef int <name>GLchar</name>;</type>
+        <type name="GLhandleARB">#ifdef __APPLE__
+typedef void *GLhandleARB;
+#else
+typedef unsigned int GLhandleARB;
+#endif</type>
+        <type>typedef struct __GLsync *<name>GLsync</name>;</type>
+        <type>typedef void (<apientry/> *<name>GLDEBUGPROC</name>)(GLenum source,GLsizei length,const GLchar *message,const void *userParam);</type>
+        <type>typedef struct { GLint x, y; GLsizei width; } <name>GLrect</name>;</type>
+    </types>
+    <groups>
+        <group name="PrimitiveType">
+            <enum name="GL_POINTS"/>
+            <enum name="GL_LINES"/>
+            <enum name="GL_QUADS"/>
+        </group>
+    </groups>
+    <enums namespace="GL">
+        <enum value="0x0000" name="GL_POINTS"/>
+        <enum value="0x0001" name="GL_LINES"/>
+        <enum value="0x0007" name="GL_QUADS"/>
+    </enums>
+    <commands namespace="GL">
+        <command>
+            <proto>void <name>glBegin</name></proto>
+            <param group="PrimitiveType"><ptype>GLenum</ptype> <name>mode</name></param>
+        </command>
+        <command>
+            <proto>void <name>glDrawArrays</name></proto>
+            <param group="PrimitiveType"><ptype>GLenum</ptype> <name>mode</name></param>
+            <param><ptype>GLint</ptype> <name>first</name></param>
+            <param><ptype>GLsizei</ptype> <name>count</name></param>
+        </command>
+        <command>
+            <proto>void <name>glDrawArraysEXT</name></proto>
+            <param group="PrimitiveType"><ptype>GLenum</ptype> <name>mode</name></param>
+            <param><ptype>GLint</ptype> <name>first</name></param>
+            <param><ptype>GLsizei</ptype> <name>count</name></param>
+            <alias name="glDrawArrays"/>
+        </command>
+        <command>
+            <proto>void <name>glBufferData</name></proto>
+            <param group="BufferTargetARB"><ptype>GLenum</ptype> <name>target</name></param>
+            <param><ptype>GLsizeiptr</ptype> <name>size</name></param>
+            <param len="size">const void *<name>data</name></param>
+            <param group="BufferUsageARB"><ptype>GLenum</ptype> <name>usage</name></param>
+        </command>
+        <command>
+            <proto>void <name>glGetIntegerv</name></proto>
+            <param group="GetPName"><ptype>GLenum</ptype> <name>pname</name></param>
+            <param len="COMPSIZE(pname)"><ptype>GLint</ptype> *<name>data</name></param>
+        </command>
+    </commands>
+    <feature api="gl" name="GL_VERSION_1_0" number="1.0">
+        <require>
+            <enum name="GL_POINTS"/>
+            <enum name="GL_LINES"/>
+            <enum name="GL_QUADS"/>
+            <command name="glBegin"/>
+        </require>
+    </feature>
+    <feature api="gl" name="GL_VERSION_1_1" number="1.1">
+        <require>
+            <command name="glDrawArrays"/>
+        </require>
+    </feature>
+    <feature api="gl" name="GL_VERSION_3_2" number="3.2">
+        <remove profile="core">
+            <enum name="GL_QUADS"/>
+            <command name="glBegin"/>
+        </remove>
+    </feature>
+    <extensions>
+        <extension name="GL_EXT_vertex_array" supported="gl">
+            <require>
+                <command name="glDrawArraysEXT"/>
+            </require>
+        </extension>
+        <extension name="GL_OES_fixed_point" supported="gles1">
+            <require>
+                <command name="glBegin"/>
+            </require>
+        </extension>
+        <extension name="GL_TEST_buffer_commands" supported="gl">
+            <require>
+                <command name="glBufferData"/>
+                <command name="glGetIntegerv"/>
+            </require>
+        </extension>
+    </extensions>
+</registry>"#;
+
+    pub fn registry(version: &str, profile: Profile) -> Registry {
+        parse_with(version, profile, &[])
+    }
+
+    /// The test registry with `glBufferData` and `glGetIntegerv`, whose pointer parameters the
+    ///  generator tests need.
+    pub fn buffer_registry(version: &str, profile: Profile) -> Registry {
+        parse_with(version, profile, &["GL_TEST_buffer_commands"])
+    }
+
+    fn parse_with(version: &str, profile: Profile, extensions: &[&str]) -> Registry {
+        let filter = parse::Filter {
+            api: Api::Gl,
+            fallbacks: Fallbacks::All,
+            extensions: extensions.iter().map(|ext| ext.to_string()).collect(),
+            profile,
+            version: version.to_string(),
+        };
+        parse::from_xml(XML.as_bytes(), &filter, true).unwrap()
+    }
+
+    #[test]
+    fn test_types() {
+        let registry = registry("3.2", Profile::Core);
+        let kind = |ident| &registry.types.iter().find(|ty| ty.ident == ident).unwrap().kind;
+
+        assert_eq!(*kind("GLenum"), TypeKind::Alias("__gl_imports::raw::c_uint".to_string()));
+        // the type of another API is skipped
+        assert_eq!(*kind("GLchar"), TypeKind::Alias("__gl_imports::raw::c_char".to_string()));
+        assert_eq!(*kind("GLsync"), TypeKind::Alias("*mut types::__GLsync".to_string()));
+        assert_eq!(*kind("__GLsync"), TypeKind::Opaque);
+
+        let handles = registry.types.iter().filter(|ty| ty.ident == "GLhandleARB");
+        let handles = handles.map(|ty| (ty.cfg.as_deref(), &ty.kind)).collect::<Vec<_>>();
+        assert_eq!(handles, [
+            (Some("target_os = \"macos\""), &TypeKind::Alias("*mut __gl_imports::raw::c_void".to_string())),
+            (Some("not(target_os = \"macos\")"), &TypeKind::Alias("__gl_imports::raw::c_uint".to_string())),
+        ]);
+
+        match kind("GLDEBUGPROC") {
+            TypeKind::FnPtr { params, ret } => {
+                assert_eq!(ret, "()");
+                let params = params.iter().map(|p| (&*p.ident, &*p.ty)).collect::<Vec<_>>();
+                assert_eq!(params, [
+                    ("source", "types::GLenum"),
+                    ("length", "types::GLsizei"),
+                    ("message", "*const types::GLchar"),
+                    ("userParam", "*const __gl_imports::raw::c_void"),
+                ]);
+            }
+            kind => panic!("Expected a function pointer, found: {:?}", kind),
+        }
+
+        match kind("GLrect") {
+            TypeKind::Struct(fields) => {
+                let fields = fields.iter().map(|f| (&*f.ident, &*f.ty)).collect::<Vec<_>>();
+                assert_eq!(fields, [("x", "types::GLint"), ("y", "types::GLint"), ("width", "types::GLsizei")]);
+            }
+            kind => panic!("Expected a struct, found: {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn test_commands_in_feature() {
+        let registry = registry("3.2", Profile::Core);
+        assert_eq!(registry.commands_in_feature("GL_VERSION_1_0").into_iter().collect::<Vec<_>>(), ["Begin"]);
+        assert_eq!(registry.commands_in_feature("GL_VERSION_1_1").into_iter().collect::<Vec<_>>(), ["DrawArrays"]);
+        assert!(registry.commands_in_feature("GL_VERSION_4_5").is_empty());
+    }
+
+    #[test]
+    fn test_introduced_in() {
+        let registry = registry("3.2", Profile::Core);
+        assert_eq!(registry.introduced_in("DrawArrays").map(|f| &*f.name), Some("GL_VERSION_1_1"));
+        assert_eq!(registry.introduced_in("DrawArraysEXT"), None);
+    }
+
+    #[test]
+    fn test_features_limited_to_version() {
+        let registry = registry("1.0", Profile::Compatibility);
+        assert_eq!(registry.features.len(), 1);
+        assert_eq!(registry.introduced_in("DrawArrays"), None);
+    }
+
+    #[test]
+    fn test_enums_in_group() {
+        let core = registry("3.2", Profile::Core);
+        let idents = core.enums_in_group("PrimitiveType").into_iter().map(|e| &*e.ident).collect::<Vec<_>>();
+        assert_eq!(idents, ["LINES", "POINTS"]);
+
+        let compat = registry("3.2", Profile::Compatibility);
+        assert_eq!(compat.enums_in_group("PrimitiveType").len(), 3);
+        assert!(compat.enums_in_group("NoSuchGroup").is_empty());
+    }
+
+    #[test]
+    fn test_extensions_providing() {
+        let registry = registry("3.2", Profile::Core);
+        let names = registry.extensions_providing("DrawArraysEXT").into_iter().map(|e| &*e.name).collect::<Vec<_>>();
+        assert_eq!(names, ["GL_EXT_vertex_array"]);
+        // extensions of other APIs are not kept
+        assert!(registry.extensions_providing("Begin").is_empty());
+    }
+
+    #[test]
+    fn test_removed_in_core() {
+        let registry = registry("3.2", Profile::Compatibility);
+        assert_eq!(registry.removed_in_core("Begin").map(|f| &*f.number), Some("3.2"));
+        assert_eq!(registry.removed_in_core("DrawArrays"), None);
+    }
+
+    #[test]
+    fn test_vecequiv() {
+        let registry = RegistryBuilder::new(Api::Gl, (2, 0)).profile(Profile::Compatibility).build().unwrap();
+        let vecequiv = |ident| registry.cmds.iter().find(|c| c.proto.ident == ident).unwrap().vecequiv.as_deref();
+        assert_eq!(vecequiv("Color3f"), Some("Color3fv"));
+        assert_eq!(vecequiv("VertexAttrib4f"), Some("VertexAttrib4fv"));
+        assert_eq!(vecequiv("Uniform3f"), None);
+    }
+
+    #[test]
+    fn test_retain_only() {
+        let mut draw = registry("3.2", Profile::Compatibility);
+        let idents = ["DrawArrays", "GL_QUADS", "types"].iter().map(|ident| ident.to_string()).collect();
+        draw.retain_only(&idents);
+        assert_eq!(draw.cmds.iter().map(|c| &*c.proto.ident).collect::<Vec<_>>(), ["DrawArrays"]);
+        // the enums of `mode`, which is a `PrimitiveType`
+        assert_eq!(draw.enums.iter().map(|e| &*e.ident).collect::<Vec<_>>(), ["LINES", "POINTS", "QUADS"]);
+
+        let mut get = buffer_registry("3.2", Profile::Compatibility);
+        let idents = ["GetIntegerv", "LINES"].iter().map(|ident| ident.to_string()).collect();
+        get.retain_only(&idents);
+        assert_eq!(get.enums.iter().map(|e| &*e.ident).collect::<Vec<_>>(), ["LINES"]);
+
+        let mut uniform = RegistryBuilder::new(Api::Gl, (4, 6)).profile(Profile::Core).build().unwrap();
+        uniform.retain_only(&["Uniform3f"].iter().map(|ident| ident.to_string()).collect());
+        assert_eq!(uniform.cmds.iter().map(|c| &*c.proto.ident).collect::<Vec<_>>(), ["Uniform3f", "Uniform3fv"]);
+        assert_eq!(uniform.aliases.keys().collect::<Vec<_>>(), ["Uniform3f", "Uniform3fv"]);
+
+        let mut map = RegistryBuilder::new(Api::Gl, (4, 6)).profile(Profile::Core).build().unwrap();
+        map.retain_only(&["MapBuffer", "Fence"].iter().map(|ident| ident.to_string()).collect());
+        assert_eq!(
+            map.cmds.iter().map(|c| &*c.proto.ident).collect::<Vec<_>>(),
+            ["ClientWaitSync", "DeleteSync", "FenceSync", "GetBufferParameteri64v", "GetSynciv", "MapBuffer", "UnmapBuffer", "WaitSync"]
+        );
+        // the fallbacks of the commands that are kept through `MapBuffer`
+        assert_eq!(map.aliases["UnmapBuffer"], ["UnmapBufferARB", "UnmapBufferOES"]);
+    }
+
+    #[test]
+    fn test_sub() {
+        let registry = registry("3.2", Profile::Compatibility) - registry("1.0", Profile::Compatibility);
+        assert_eq!(registry.cmds.iter().map(|c| &*c.proto.ident).collect::<Vec<_>>(), ["DrawArrays"]);
+        assert!(registry.enums.is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        let compat = registry("3.2", Profile::Compatibility);
+        let core = registry("3.2", Profile::Core);
+
+        let diff = compat.diff(&core);
+        assert!(diff.added_cmds.is_empty() && diff.added_enums.is_empty());
+        assert_eq!(diff.removed_cmds.iter().collect::<Vec<_>>(), ["Begin"]);
+        assert_eq!(diff.removed_enums.iter().collect::<Vec<_>>(), ["QUADS"]);
+
+        let reverse = core.diff(&compat);
+        assert_eq!(reverse.added_cmds, diff.removed_cmds);
+        assert_eq!(reverse.added_enums, diff.removed_enums);
+        assert!(compat.diff(&compat).is_empty());
     }
 }
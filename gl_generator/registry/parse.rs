@@ -13,12 +13,32 @@ use std::{
 };
 use xml::{attribute::OwnedAttribute, reader::XmlEvent, EventReader as XmlEventReader};
 
-use registry::{Binding, Cmd, DebugPrints, Enum, GlxOpcode, Group, Registry};
+use generators::GeneratorOptions;
+use registry::{
+    Binding, Cmd, Enum, Extension, Feature, GlxOpcode, Group, Registry, RegistryError, Remove,
+    Require, TypeDef, TypeKind,
+};
 use Api;
 use Fallbacks;
 use Profile;
 
-pub fn from_xml<R: io::Read>(src: R, filter: &Filter, require_feature: bool) -> Registry {
+/// The version of the parser's output, part of the key of cached registries. Bump it whenever a
+///  change here makes the same registry XML parse to a different `Registry`.
+#[cfg(feature = "serde")]
+pub const PARSER_VERSION: u32 = 2;
+
+/// The types which the registry only has placeholders for, but which the platform headers define
+///  the same way on every platform, with their C types.
+const PLATFORM_TYPEDEFS: &[(&str, &str)] = &[
+    // eglplatform.h
+    ("EGLint", "khronos_int32_t"),
+];
+
+pub fn from_xml<R: io::Read>(
+    src: R,
+    filter: &Filter,
+    require_feature: bool,
+) -> Result<Registry, RegistryError> {
     XmlEventReader::new(src)
         .into_iter()
         .map(Result::unwrap)
@@ -79,7 +99,7 @@ impl ParseEvent {
     }
 }
 
-fn api_from_str(src: &str) -> Result<Option<Api>, ()> {
+pub fn api_from_str(src: &str) -> Result<Option<Api>, ()> {
     match src {
         "gl" => Ok(Some(Api::Gl)),
         "glx" => Ok(Some(Api::Glx)),
@@ -94,7 +114,7 @@ fn api_from_str(src: &str) -> Result<Option<Api>, ()> {
     }
 }
 
-fn profile_from_str(src: &str) -> Result<Profile, ()> {
+pub fn profile_from_str(src: &str) -> Result<Profile, ()> {
     match src {
         "core" => Ok(Profile::Core),
         "compatibility" => Ok(Profile::Compatibility),
@@ -236,39 +256,39 @@ fn merge_map(a: &mut BTreeMap<String, Vec<String>>, b: BTreeMap<String, Vec<Stri
     }
 }
 
-#[derive(Clone)]
-struct Feature {
-    pub api: Api,
-    pub name: String,
-    pub number: String,
-    pub requires: Vec<Require>,
-    pub removes: Vec<Remove>,
-}
-
-#[derive(Clone)]
-struct Require {
-    /// A reference to the earlier types, by name
-    pub enums: Vec<String>,
-    /// A reference to the earlier types, by name
-    pub commands: Vec<String>,
+/// The error for the parse event `found` where `expected` was expected, `None` being the end of the
+///  document.
+fn unexpected_xml(expected: &str, found: Option<ParseEvent>) -> RegistryError {
+    RegistryError::UnexpectedXml {
+        expected: expected.to_string(),
+        found:    match found {
+            Some(event) => format!("{:?}", event),
+            None => "the end of the document".to_string(),
+        },
+    }
 }
 
-#[derive(Clone)]
-struct Remove {
-    // always Core, for now
-    pub profile: Profile,
-    /// A reference to the earlier types, by name
-    pub enums: Vec<String>,
-    /// A reference to the earlier types, by name
-    pub commands: Vec<String>,
+/// Strips the API prefixes from the enum and command names, so they match `Enum::ident` and
+///  `Cmd::proto.ident`.
+fn trim_requires(requires: &[Require], api: Api) -> Vec<Require> {
+    requires
+        .iter()
+        .map(|require| Require {
+            enums: require.enums.iter().map(|e| trim_enum_prefix(e, api)).collect(),
+            commands: require.commands.iter().map(|c| trim_cmd_prefix(c, api).to_string()).collect(),
+        })
+        .collect()
 }
 
-#[derive(Clone)]
-struct Extension {
-    pub name: String,
-    /// which apis this extension is defined for (see Feature.api)
-    pub supported: Vec<Api>,
-    pub requires: Vec<Require>,
+fn trim_removes(removes: &[Remove], api: Api) -> Vec<Remove> {
+    removes
+        .iter()
+        .map(|remove| Remove {
+            profile: remove.profile,
+            enums: remove.enums.iter().map(|e| trim_enum_prefix(e, api)).collect(),
+            commands: remove.commands.iter().map(|c| trim_cmd_prefix(c, api).to_string()).collect(),
+        })
+        .collect()
 }
 
 pub struct Filter {
@@ -277,11 +297,10 @@ pub struct Filter {
     pub extensions: BTreeSet<String>,
     pub profile: Profile,
     pub version: String,
-    pub debug_prints: DebugPrints,
 }
 
 trait Parse: Sized + Iterator<Item = ParseEvent> {
-    fn parse(mut self, filter: &Filter, require_feature: bool) -> Registry {
+    fn parse(mut self, filter: &Filter, require_feature: bool) -> Result<Registry, RegistryError> {
         self.consume_start_element("registry");
 
         let mut enums = Vec::new();
@@ -290,13 +309,18 @@ trait Parse: Sized + Iterator<Item = ParseEvent> {
         let mut extensions = Vec::new();
         let mut aliases = BTreeMap::new();
         let mut groups: BTreeMap<String, Group> = BTreeMap::new();
+        let mut types = Vec::new();
 
         while let Some(event) = self.next() {
             match event {
                 // ignores
                 ParseEvent::Text(_) => (),
                 ParseEvent::Start(ref name, _) if name == "comment" => self.skip_to_end("comment"),
-                ParseEvent::Start(ref name, _) if name == "types" => self.skip_to_end("types"),
+
+                // add type definitions
+                ParseEvent::Start(ref name, _) if name == "types" => {
+                    types.extend(self.consume_types(filter.api)?);
+                }
 
                 // add group namespace
                 ParseEvent::Start(ref name, _) if name == "groups" => {
@@ -375,16 +399,19 @@ trait Parse: Sized + Iterator<Item = ParseEvent> {
         }
 
         if !found_feature && require_feature {
-            panic!("Did not find version {} in the registry", filter.version);
+            return Err(RegistryError::UnknownVersion {
+                api: filter.api,
+                version: filter.version.clone(),
+            });
         }
 
         for extension in &extensions {
             if filter.extensions.contains(&extension.name) {
                 if !extension.supported.contains(&filter.api) {
-                    panic!(
-                        "Requested {}, which doesn't support the {} API",
-                        extension.name, filter.api
-                    );
+                    return Err(RegistryError::UnsupportedExtension {
+                        extension: extension.name.clone(),
+                        api: filter.api,
+                    });
                 }
                 for require in &extension.requires {
                     desired_enums.extend(require.enums.iter().map(|x| x.clone()));
@@ -407,7 +434,7 @@ trait Parse: Sized + Iterator<Item = ParseEvent> {
                 || desired_cmds.contains(&("egl".to_string() + &c.proto.ident))
         };
 
-        Registry {
+        Ok(Registry {
             api: filter.api,
             enums: enums.into_iter().filter(is_desired_enum).collect(),
             cmds: cmds.into_iter().filter(is_desired_cmd).collect(),
@@ -417,8 +444,26 @@ trait Parse: Sized + Iterator<Item = ParseEvent> {
                 aliases
             },
             groups,
-            debug_prints: filter.debug_prints,
-        }
+            types,
+            features: features
+                .into_iter()
+                .filter(|feature| feature.api == filter.api && feature.number <= filter.version)
+                .map(|feature| Feature {
+                    requires: trim_requires(&feature.requires, filter.api),
+                    removes: trim_removes(&feature.removes, filter.api),
+                    ..feature
+                })
+                .collect(),
+            extensions: extensions
+                .into_iter()
+                .filter(|extension| extension.supported.contains(&filter.api))
+                .map(|extension| Extension {
+                    requires: trim_requires(&extension.requires, filter.api),
+                    ..extension
+                })
+                .collect(),
+            options: GeneratorOptions::default(),
+        })
     }
 
     fn consume_characters(&mut self) -> String {
@@ -512,6 +557,127 @@ trait Parse: Sized + Iterator<Item = ParseEvent> {
         }
     }
 
+    fn consume_types(&mut self, api: Api) -> Result<Vec<TypeDef>, RegistryError> {
+        let mut types: Vec<TypeDef> = Vec::new();
+        let mut tags = BTreeSet::new();
+        loop {
+            match self.next() {
+                Some(ParseEvent::Start(ref name, ref attributes)) if name == "type" => {
+                    let defs = self.consume_type(attributes, &mut tags)?;
+                    // types for a specific API replace the generic ones of the same name
+                    match get_attribute(attributes, "api") {
+                        Some(ref type_api) if *type_api != api.to_string() => continue,
+                        _ => (),
+                    }
+                    types.retain(|ty| defs.iter().all(|def| def.ident != ty.ident));
+                    types.extend(defs);
+                }
+                Some(ParseEvent::End(ref name)) if name == "types" => break,
+                event => return Err(unexpected_xml("</types>", event)),
+            }
+        }
+
+        // structs only named by pointers, like `struct __GLsync *`, are opaque
+        for tag in tags {
+            if types.iter().all(|ty| ty.ident != tag) {
+                types.push(TypeDef {
+                    ident: tag,
+                    cfg: None,
+                    kind: TypeKind::Opaque,
+                });
+            }
+        }
+        Ok(types)
+    }
+
+    /// Consumes a `<type>` and returns its definitions, which are several for types that differ by
+    ///  platform, or none for preprocessor directives like `#include <KHR/khrplatform.h>`.
+    fn consume_type(&mut self, attributes: &[Attribute], tags: &mut BTreeSet<String>) -> Result<Vec<TypeDef>, RegistryError> {
+        // the declaration before and after the `<name>`
+        let mut before = String::new();
+        let mut ident = None;
+        let mut after = String::new();
+        loop {
+            match self.next() {
+                Some(ParseEvent::Text(text)) => match ident {
+                    None => before.push_str(&text),
+                    Some(_) => after.push_str(&text),
+                },
+                Some(ParseEvent::Start(ref name, _)) if name == "name" => {
+                    ident = Some(self.consume_characters());
+                    self.consume_end_element("name");
+                }
+                Some(ParseEvent::Start(ref name, _)) if name == "apientry" => (),
+                Some(ParseEvent::End(ref name)) if name == "apientry" => (),
+                Some(ParseEvent::End(ref name)) if name == "type" => break,
+                event => return Err(unexpected_xml("</type>", event)),
+            }
+        }
+
+        let ident = match ident {
+            Some(ident) => ident,
+            None => {
+                let ident = match get_attribute(attributes, "name") {
+                    Some(ident) => ident,
+                    None => return Err(unexpected_xml("<name> in <type>", Some(ParseEvent::End("type".to_string())))),
+                };
+                let platform_ty = PLATFORM_TYPEDEFS.iter().find(|&&(platform_ident, _)| platform_ident == ident);
+                return Ok(match before.trim() {
+                    "" => vec![TypeDef {
+                        ident,
+                        cfg: None,
+                        kind: match platform_ty {
+                            Some(&(_, ty)) => TypeKind::Alias(pointer_ty(ty)),
+                            None => TypeKind::External,
+                        },
+                    }],
+                    src => parse_conditional_typedefs(&ident, src, tags),
+                });
+            }
+        };
+
+        let before = before.trim();
+        let kind = if let Some(decl) = before.strip_prefix("typedef") {
+            let decl = decl.trim();
+            if let Some(open) = decl.find('{') {
+                let fields = parse_fields(&decl[open + 1..decl.rfind('}').unwrap_or(decl.len())], tags);
+                match decl.starts_with("union") {
+                    true => TypeKind::Union(fields),
+                    false => TypeKind::Struct(fields),
+                }
+            } else if after.trim_start().starts_with(')') {
+                // `typedef R (*name)(params);`
+                let params = &after[after.find('(').unwrap_or(0) + 1..after.rfind(')').unwrap_or(0)];
+                TypeKind::FnPtr {
+                    params: parse_params(params, tags),
+                    ret: pointer_ty(&decl[..decl.find('(').unwrap_or(decl.len())]),
+                }
+            } else {
+                tags.extend(struct_tag(decl));
+                match decl {
+                    // `typedef void GLvoid;` is only used behind pointers
+                    "void" => TypeKind::Alias(base_ty(decl, true)),
+                    decl => TypeKind::Alias(pointer_ty(decl)),
+                }
+            }
+        } else if before.starts_with("DECLARE_HANDLE") {
+            TypeKind::Handle
+        } else if before.starts_with("struct") && after.contains('{') {
+            TypeKind::Struct(parse_fields(&after[after.find('{').unwrap() + 1..after.rfind('}').unwrap_or(after.len())], tags))
+        } else if before.starts_with("struct") || ident.starts_with("struct ") {
+            TypeKind::Opaque
+        } else {
+            warn!("Skipping unknown type declaration `{}{}{}`", before, ident, after);
+            return Ok(Vec::new());
+        };
+
+        Ok(vec![TypeDef {
+            ident: trim_str(&ident, "struct ").to_string(),
+            cfg: None,
+            kind,
+        }])
+    }
+
     fn consume_enums(&mut self, api: Api) -> Vec<Enum> {
         let mut enums = Vec::new();
         loop {
@@ -633,7 +799,7 @@ trait Parse: Sized + Iterator<Item = ParseEvent> {
                     self.consume_end_element("alias");
                 }
                 ParseEvent::Start(ref name, ref attributes) if name == "vecequiv" => {
-                    vecequiv = get_attribute(&attributes, "vecequiv");
+                    vecequiv = get_attribute(attributes, "name").map(|t| trim_cmd_prefix(&t, api).to_string());
                     self.consume_end_element("vecequiv");
                 }
                 ParseEvent::Start(ref name, ref attributes) if name == "glx" => {
@@ -687,6 +853,7 @@ trait Parse: Sized + Iterator<Item = ParseEvent> {
             ident,
             ty: to_rust_ty(ty),
             group: get_attribute(&attributes, "group"),
+            len: get_attribute(attributes, "len"),
         }
     }
 }
@@ -785,304 +952,215 @@ impl FromXml for String {
     }
 }
 
-/// Converts a C style type definition to the Rust equivalent
+/// Converts a C type to the Rust equivalent, e.g. `const GLchar *const*` to
+///  `*const *const types::GLchar`.
+///
+/// Named types refer to the `types` module, apart from the C and fixed-size integer types, which
+///  have Rust equivalents. An array parameter like `GLuint [2]` becomes a pointer to an array.
 pub fn to_rust_ty<T: AsRef<str>>(ty: T) -> Cow<'static, str> {
-    let ty = match ty.as_ref().trim() {
-        // gl.xml types
-        "GLDEBUGPROC" => "types::GLDEBUGPROC",
-        "GLDEBUGPROCAMD" => "types::GLDEBUGPROCAMD",
-        "GLDEBUGPROCARB" => "types::GLDEBUGPROCARB",
-        "GLDEBUGPROCKHR" => "types::GLDEBUGPROCKHR",
-        "GLbitfield" => "types::GLbitfield",
-        "GLboolean" => "types::GLboolean",
-        "GLbyte" => "types::GLbyte",
-        "GLclampd" => "types::GLclampd",
-        "GLclampf" => "types::GLclampf",
-        "GLclampx" => "types::GLclampx",
-        "GLdouble" => "types::GLdouble",
-        "GLeglImageOES" => "types::GLeglImageOES",
-        "GLenum" => "types::GLenum",
-        "GLfixed" => "types::GLfixed",
-        "GLfloat" => "types::GLfloat",
-        "GLhalfNV" => "types::GLhalfNV",
-        "GLhandleARB" => "types::GLhandleARB",
-        "GLint" => "types::GLint",
-        "GLint64" => "types::GLint64",
-        "GLint64EXT" => "types::GLint64EXT",
-        "GLintptr" => "types::GLintptr",
-        "GLintptrARB" => "types::GLintptrARB",
-        "GLshort" => "types::GLshort",
-        "GLsizei" => "types::GLsizei",
-        "GLsizeiptr" => "types::GLsizeiptr",
-        "GLsizeiptrARB" => "types::GLsizeiptrARB",
-        "GLsync" => "types::GLsync",
-        "GLubyte" => "types::GLubyte",
-        "GLuint" => "types::GLuint",
-        "GLuint64" => "types::GLuint64",
-        "GLuint64EXT" => "types::GLuint64EXT",
-        "GLushort" => "types::GLushort",
-        "GLvdpauSurfaceNV" => "types::GLvdpauSurfaceNV",
-        "void" => "()",
-        "GLboolean *" => "*mut types::GLboolean",
-        "GLchar *" => "*mut types::GLchar",
-        "const GLchar*" => "*const types::GLchar",
-        "GLcharARB *" => "*mut types::GLcharARB",
-        "GLdouble *" => "*mut types::GLdouble",
-        "GLenum *" => "*mut types::GLenum",
-        "GLfixed *" => "*mut types::GLfixed",
-        "GLfloat *" => "*mut types::GLfloat",
-        "GLhandleARB *" => "*mut types::GLhandleARB",
-        "GLint *" => "*mut types::GLint",
-        "GLint64 *" => "*mut types::GLint64",
-        "GLint64EXT *" => "*mut types::GLint64EXT",
-        "GLsizei *" => "*mut types::GLsizei",
-        "GLubyte *" => "*mut types::GLubyte",
-        "GLuint *" => "*mut types::GLuint",
-        "GLuint [2]" => "*mut [types::GLuint; 2]",
-        "GLuint64 *" => "*mut types::GLuint64",
-        "GLuint64EXT *" => "*mut types::GLuint64EXT",
-        "GLushort *" => "*mut types::GLushort",
-        "GLvoid *" => "*mut types::GLvoid",
-        "GLvoid **" => "*const *mut types::GLvoid",
-        "void *" => "*mut __gl_imports::raw::c_void",
-        "void **" => "*const *mut __gl_imports::raw::c_void",
-        "const GLboolean *" => "*const types::GLboolean",
-        "const GLbyte *" => "*const types::GLbyte",
-        "const GLchar *" => "*const types::GLchar",
-        "const GLcharARB *" => "*const types::GLcharARB",
-        "const GLclampf *" => "*const types::GLclampf",
-        "const GLdouble *" => "*const types::GLdouble",
-        "const GLenum *" => "*const types::GLenum",
-        "const GLfixed *" => "*const types::GLfixed",
-        "const GLfloat" => "types::GLfloat",
-        "const GLfloat *" => "*const types::GLfloat",
-        "const GLhalfNV *" => "*const types::GLhalfNV",
-        "const GLint *" => "*const types::GLint",
-        "const GLint*" => "*const types::GLint",
-        "const GLint64 *" => "*const types::GLint64",
-        "const GLint64EXT *" => "*const types::GLint64EXT",
-        "const GLintptr *" => "*const types::GLintptr",
-        "const GLshort *" => "*const types::GLshort",
-        "const GLsizei*" | "const GLsizei *" => "*const types::GLsizei",
-        "const GLsizeiptr *" => "*const types::GLsizeiptr",
-        "const GLubyte *" => "*const types::GLubyte",
-        "const GLuint *" => "*const types::GLuint",
-        "const GLuint64 *" => "*const types::GLuint64",
-        "const GLuint64EXT *" => "*const types::GLuint64EXT",
-        "const GLushort *" => "*const types::GLushort",
-        "const GLvdpauSurfaceNV *" => "*const types::GLvdpauSurfaceNV",
-        "const GLvoid *" => "*const types::GLvoid",
-        "const void*" | "const void *" => "*const __gl_imports::raw::c_void",
-        "const void **" => "*const *const __gl_imports::raw::c_void",
-        "const void *const*" => "*const *const __gl_imports::raw::c_void",
-        "const GLboolean **" => "*const *const types::GLboolean",
-        "const GLchar **" => "*const *const types::GLchar",
-        "const GLcharARB **" => "*const *const types::GLcharARB",
-        "const GLvoid **" => "*const *const types::GLvoid",
-        "const GLchar *const*" => "*const *const types::GLchar",
-        "const GLvoid *const*" => "*const *const types::GLvoid",
-        "struct _cl_context *" => "*const types::_cl_context",
-        "struct _cl_event *" => "*const types::_cl_event",
-        "GLuint[2]" => "[Gluint; 2]",
-
-        // glx.xml types
-        "Bool" => "types::Bool",
-        "Colormap" => "types::Colormap",
-        "DMbuffer" => "types::DMbuffer",
-        "Font" => "types::Font",
-        "GLXContext" => "types::GLXContext",
-        "GLXContextID" => "types::GLXContextID",
-        "GLXDrawable" => "types::GLXDrawable",
-        "GLXFBConfig" => "types::GLXFBConfig",
-        "GLXFBConfigSGIX" => "types::GLXFBConfigSGIX",
-        "GLXPbuffer" => "types::GLXPbuffer",
-        "GLXPbufferSGIX" => "types::GLXPbufferSGIX",
-        "GLXPixmap" => "types::GLXPixmap",
-        "GLXVideoCaptureDeviceNV" => "types::GLXVideoCaptureDeviceNV",
-        "GLXVideoDeviceNV" => "types::GLXVideoDeviceNV",
-        "GLXVideoSourceSGIX" => "types::GLXVideoSourceSGIX",
-        "GLXWindow" => "types::GLXWindow",
-        // "GLboolean"                 => "types::GLboolean",
-        // "GLenum"                    => "types::GLenum",
-        // "GLint"                     => "types::GLint",
-        // "GLsizei"                   => "types::GLsizei",
-        // "GLuint"                    => "types::GLuint",
-        "Pixmap" => "types::Pixmap",
-        "Status" => "types::Status",
-        "VLNode" => "types::VLNode",
-        "VLPath" => "types::VLPath",
-        "VLServer" => "types::VLServer",
-        "Window" => "types::Window",
-        "__GLXextFuncPtr" => "types::__GLXextFuncPtr",
-        "const GLXContext" => "const types::GLXContext",
-        "float" => "__gl_imports::raw::c_float",
-        "int" => "__gl_imports::raw::c_int",
-        "int64_t" => "i64",
-        "unsigned int" => "__gl_imports::raw::c_uint",
-        "unsigned long" => "__gl_imports::raw::c_ulong",
-        // "void "                     => "()",
-        "DMparams *" => "*mut types::DMparams",
-        "Display *" => "*mut types::Display",
-        "GLXFBConfig *" => "*mut types::GLXFBConfig",
-        "GLXFBConfigSGIX *" => "*mut types::GLXFBConfigSGIX",
-        "GLXHyperpipeConfigSGIX *" => "*mut types::GLXHyperpipeConfigSGIX",
-        "GLXHyperpipeNetworkSGIX *" => "*mut types::GLXHyperpipeNetworkSGIX",
-        "GLXVideoCaptureDeviceNV *" => "*mut types::GLXVideoCaptureDeviceNV",
-        "GLXVideoDeviceNV *" => "*mut types::GLXVideoDeviceNV",
-        // "GLuint *"                  => "*mut types::GLuint",
-        "XVisualInfo *" => "*mut types::XVisualInfo",
-        // "const GLubyte *"           => "*GLubyte",
-        "const char *" => "*const __gl_imports::raw::c_char",
-        "const int *" => "*const __gl_imports::raw::c_int",
-        // "const void *"              => "*const __gl_imports::raw::c_void",
-        "int *" => "*mut __gl_imports::raw::c_int",
-        "int32_t *" => "*mut i32",
-        "int64_t *" => "*mut i64",
-        "long *" => "*mut __gl_imports::raw::c_long",
-        "unsigned int *" => "*mut __gl_imports::raw::c_uint",
-        "unsigned long *" => "*mut __gl_imports::raw::c_ulong",
-        // "void *"                    => "*mut __gl_imports::raw::c_void",
-
-        // wgl.xml types
-        "BOOL" => "types::BOOL",
-        "DWORD" => "types::DWORD",
-        "FLOAT" => "types::FLOAT",
-        // "GLbitfield"                => "types::GLbitfield",
-        // "GLboolean"                 => "types::GLboolean",
-        // "GLenum"                    => "types::GLenum",
-        // "GLfloat"                   => "types::GLfloat",
-        // "GLint"                     => "types::GLint",
-        // "GLsizei"                   => "types::GLsizei",
-        // "GLuint"                    => "types::GLuint",
-        // "GLushort"                  => "types::GLushort",
-        "HANDLE" => "types::HANDLE",
-        "HDC" => "types::HDC",
-        "HENHMETAFILE" => "types::HENHMETAFILE",
-        "HGLRC" => "types::HGLRC",
-        "HGPUNV" => "types::HGPUNV",
-        "HPBUFFERARB" => "types::HPBUFFERARB",
-        "HPBUFFEREXT" => "types::HPBUFFEREXT",
-        "HPVIDEODEV" => "types::HPVIDEODEV",
-        "HVIDEOINPUTDEVICENV" => "types::HVIDEOINPUTDEVICENV",
-        "HVIDEOOUTPUTDEVICENV" => "types::HVIDEOOUTPUTDEVICENV",
-        "INT" => "types::INT",
-        "INT64" => "types::INT64",
-        "LPCSTR" => "types::LPCSTR",
-        "LPGLYPHMETRICSFLOAT" => "types::LPGLYPHMETRICSFLOAT",
-        "LPVOID" => "types::LPVOID",
-        "PGPU_DEVICE" => "types::PGPU_DEVICE",
-        "PROC" => "types::PROC",
-        "UINT" => "types::UINT",
-        "VOID" => "types::VOID",
-        // "int "                      => "__gl_imports::raw::c_int",
-        // "unsigned int "             => "__gl_imports::raw::c_uint",
-        // "void "                     => "()",
-        "BOOL *" => "*mut types::BOOL",
-        "DWORD *" => "*mut types::DWORD",
-        "FLOAT *" => "*mut types::FLOAT",
-        // "GLuint *"                  => "*mut types::GLuint",
-        "HANDLE *" => "*mut types::HANDLE",
-        "HGPUNV *" => "*mut types::HGPUNV",
-        "HPVIDEODEV *" => "*mut types::HPVIDEODEV",
-        "HVIDEOINPUTDEVICENV *" => "*mut types::HVIDEOINPUTDEVICENV",
-        "HVIDEOOUTPUTDEVICENV *" => "*mut types::HVIDEOOUTPUTDEVICENV",
-        "INT32 *" => "*mut types::INT32",
-        "INT64 *" => "*mut types::INT64",
-        "UINT *" => "*mut types::UINT",
-        "USHORT *" => "*mut types::USHORT",
-        "const COLORREF *" => "*const types::COLORREF",
-        "const DWORD *" => "*const types::DWORD",
-        "const FLOAT *" => "*const types::FLOAT",
-        // "const GLushort *"          => "*const types::GLushort",
-        "const HANDLE *" => "*const types::HANDLE",
-        "const HGPUNV *" => "*const types::HGPUNV",
-        "const LAYERPLANEDESCRIPTOR *" => "*const types::LAYERPLANEDESCRIPTOR",
-        "const LPVOID *" => "*const types::LPVOID",
-        "const PIXELFORMATDESCRIPTOR *" => "*const types::IXELFORMATDESCRIPTOR",
-        "const USHORT *" => "*const types::USHORT",
-        // "const char *"              => "*const __gl_imports::raw::c_char",
-        // "const int *"               => "*const __gl_imports::raw::c_int",
-        "float *" => "*mut __gl_imports::raw::c_float",
-        // "int *"                     => "*mut __gl_imports::raw::c_int",
-        // "unsigned long *"           => "*mut __gl_imports::raw::c_ulong",
-        // "void *"                    => "*mut __gl_imports::raw::c_void",
-
-        // elx.xml types
-        "khronos_utime_nanoseconds_t" => "types::khronos_utime_nanoseconds_t",
-        "khronos_uint64_t" => "types::khronos_uint64_t",
-        "khronos_ssize_t" => "types::khronos_ssize_t",
-        "EGLNativeDisplayType" => "types::EGLNativeDisplayType",
-        "EGLNativePixmapType" => "types::EGLNativePixmapType",
-        "EGLNativeWindowType" => "types::EGLNativeWindowType",
-        "EGLint" => "types::EGLint",
-        "EGLint *" => "*mut types::EGLint",
-        "const EGLint *" => "*const types::EGLint",
-        "NativeDisplayType" => "types::NativeDisplayType",
-        "NativePixmapType" => "types::NativePixmapType",
-        "NativeWindowType" => "types::NativeWindowType",
-        //"Bool"                      => "types::Bool",
-        "EGLBoolean" => "types::EGLBoolean",
-        "EGLenum" => "types::EGLenum",
-        "EGLAttribKHR" => "types::EGLAttribKHR",
-        "EGLAttrib" => "types::EGLAttrib",
-        "EGLAttrib *" => "*mut types::EGLAttrib",
-        "const EGLAttrib *" => "*const types::EGLAttrib",
-        "EGLConfig" => "types::EGLConfig",
-        "EGLConfig *" => "*mut types::EGLConfig",
-        "EGLContext" => "types::EGLContext",
-        "EGLDeviceEXT" => "types::EGLDeviceEXT",
-        "EGLDisplay" => "types::EGLDisplay",
-        "EGLSurface" => "types::EGLSurface",
-        "EGLClientBuffer" => "types::EGLClientBuffer",
-        "__eglMustCastToProperFunctionPointerType" => {
-            "types::__eglMustCastToProperFunctionPointerType"
+    let ty = ty.as_ref();
+    let rust_ty = match split_array(ty) {
+        (elem, Some(len)) => {
+            let ptr = if elem.split_whitespace().next() == Some("const") { "*const" } else { "*mut" };
+            format!("{} [{}; {}]", ptr, pointer_ty(elem), len)
+        },
+        (_, None) => pointer_ty(ty),
+    };
+    Cow::Owned(rust_ty)
+}
+
+/// Converts the C type of a struct field to the Rust equivalent, where arrays are values.
+fn field_ty(ty: &str) -> String {
+    match split_array(ty) {
+        (elem, Some(len)) => format!("[{}; {}]", pointer_ty(elem), len),
+        (_, None) => pointer_ty(ty),
+    }
+}
+
+/// Splits `char [80]` into `char` and the length `80`.
+fn split_array(ty: &str) -> (&str, Option<&str>) {
+    match (ty.find('['), ty.rfind(']')) {
+        (Some(start), Some(end)) if start < end => (&ty[..start], Some(ty[start + 1..end].trim())),
+        _ => (ty, None),
+    }
+}
+
+/// Converts a C type made of a base type and pointers, which are `*const` if what they point to is
+///  `const`. A `const` on the value itself is dropped, as Rust has no equivalent.
+fn pointer_ty(ty: &str) -> String {
+    let ty = ty.replace('*', " * ");
+    let mut base = Vec::new();
+    let mut base_const = false;
+    // whether each pointer is itself `const`, innermost first
+    let mut ptrs = Vec::new();
+    for word in ty.split_whitespace() {
+        match word {
+            "*" => ptrs.push(false),
+            "const" => match ptrs.last_mut() {
+                Some(ptr_const) => *ptr_const = true,
+                None => base_const = true,
+            },
+            "struct" | "union" => (),
+            word => base.push(word),
         }
-        "EGLImageKHR" => "types::EGLImageKHR",
-        "EGLImage" => "types::EGLImage",
-        "EGLOutputLayerEXT" => "types::EGLOutputLayerEXT",
-        "EGLOutputPortEXT" => "types::EGLOutputPortEXT",
-        "EGLSyncKHR" => "types::EGLSyncKHR",
-        "EGLSync" => "types::EGLSync",
-        "EGLTimeKHR" => "types::EGLTimeKHR",
-        "EGLTime" => "types::EGLTime",
-        "EGLSyncNV" => "types::EGLSyncNV",
-        "EGLTimeNV" => "types::EGLTimeNV",
-        "EGLuint64NV" => "types::EGLuint64NV",
-        "EGLStreamKHR" => "types::EGLStreamKHR",
-        "EGLuint64KHR" => "types::EGLuint64KHR",
-        "EGLNativeFileDescriptorKHR" => "types::EGLNativeFileDescriptorKHR",
-        "EGLsizeiANDROID" => "types::EGLsizeiANDROID",
-        "EGLSetBlobFuncANDROID" => "types::EGLSetBlobFuncANDROID",
-        "EGLGetBlobFuncANDROID" => "types::EGLGetBlobFuncANDROID",
-        "EGLClientPixmapHI" => "types::EGLClientPixmapHI",
-        "struct EGLClientPixmapHI *" => "*const types::EGLClientPixmapHI",
-        "const EGLAttribKHR *" => "*const types::EGLAttribKHR",
-        "const EGLuint64KHR *" => "*const types::EGLuint64KHR",
-        "EGLAttribKHR *" => "*mut types::EGLAttribKHR",
-        "EGLDeviceEXT *" => "*mut types::EGLDeviceEXT",
-        "EGLNativeDisplayType *" => "*mut types::EGLNativeDisplayType",
-        "EGLNativePixmapType *" => "*mut types::EGLNativePixmapType",
-        "EGLNativeWindowType *" => "*mut types::EGLNativeWindowType",
-        "EGLOutputLayerEXT *" => "*mut types::EGLOutputLayerEXT",
-        "EGLTimeKHR *" => "*mut types::EGLTimeKHR",
-        "EGLOutputPortEXT *" => "*mut types::EGLOutputPortEXT",
-        "EGLuint64KHR *" => "*mut types::EGLuint64KHR",
-        "const struct AHardwareBuffer *" => "*const __gl_imports::raw::c_void", // humm
-
-        "GLeglClientBufferEXT" => "types::GLeglClientBufferEXT",
-        "GLVULKANPROCNV" => "types::GLVULKANPROCNV",
-        "EGLDEBUGPROCKHR" => "types::EGLDEBUGPROCKHR",
-        "EGLObjectKHR" => "types::EGLObjectKHR",
-        "EGLLabelKHR" => "types::EGLLabelKHR",
-        "EGLnsecsANDROID" => "types::EGLnsecsANDROID",
-        "EGLnsecsANDROID *" => "*mut types::EGLnsecsANDROID",
-        "EGLBoolean *" => "*mut types::EGLBoolean",
-
-        // failure
-        _ => panic!("Type conversion not implemented for `{}`", ty.as_ref()),
+    }
+
+    let mut rust_ty = base_ty(&base.join(" "), !ptrs.is_empty());
+    let mut pointee_const = base_const;
+    for ptr_const in ptrs {
+        rust_ty = format!("{} {}", if pointee_const { "*const" } else { "*mut" }, rust_ty);
+        pointee_const = ptr_const;
+    }
+    rust_ty
+}
+
+/// Converts a C base type, e.g. `unsigned int` or `GLenum`, where `void` is `()` unless it is
+///  pointed to.
+fn base_ty(ty: &str, pointed_to: bool) -> String {
+    let rust_ty = match ty {
+        "void" if pointed_to => "__gl_imports::raw::c_void",
+        "void" => "()",
+        "char" => "__gl_imports::raw::c_char",
+        "signed char" => "__gl_imports::raw::c_schar",
+        "unsigned char" => "__gl_imports::raw::c_uchar",
+        "short" | "short int" => "__gl_imports::raw::c_short",
+        "unsigned short" | "unsigned short int" => "__gl_imports::raw::c_ushort",
+        "int" | "signed int" | "signed" => "__gl_imports::raw::c_int",
+        "unsigned int" | "unsigned" => "__gl_imports::raw::c_uint",
+        "long" | "long int" => "__gl_imports::raw::c_long",
+        "unsigned long" | "unsigned long int" => "__gl_imports::raw::c_ulong",
+        "long long" | "long long int" => "__gl_imports::raw::c_longlong",
+        "unsigned long long" | "unsigned long long int" => "__gl_imports::raw::c_ulonglong",
+        "float" | "khronos_float_t" => "__gl_imports::raw::c_float",
+        "double" => "__gl_imports::raw::c_double",
+        "int8_t" | "khronos_int8_t" => "i8",
+        "uint8_t" | "khronos_uint8_t" => "u8",
+        "int16_t" | "khronos_int16_t" => "i16",
+        "uint16_t" | "khronos_uint16_t" => "u16",
+        "int32_t" | "khronos_int32_t" => "i32",
+        "uint32_t" | "khronos_uint32_t" => "u32",
+        "int64_t" | "khronos_int64_t" | "khronos_stime_nanoseconds_t" => "i64",
+        "uint64_t" | "khronos_uint64_t" | "khronos_utime_nanoseconds_t" => "u64",
+        "intptr_t" | "ptrdiff_t" | "ssize_t" | "khronos_intptr_t" | "khronos_ssize_t" => "isize",
+        "uintptr_t" | "size_t" | "khronos_uintptr_t" | "khronos_usize_t" => "usize",
+        name => return format!("types::{}", name),
+    };
+    rust_ty.to_string()
+}
+
+/// Returns the tag of a `struct` named by a C type, e.g. `__GLsync` for `struct __GLsync *`.
+fn struct_tag(ty: &str) -> Option<String> {
+    let ty = ty.replace('*', " ");
+    let mut words = ty.split_whitespace().skip_while(|&word| word != "struct");
+    words.next().and(words.next()).map(str::to_string)
+}
+
+/// Splits a C declaration like `const char *name[80]` into the type `const char *[80]` and the
+///  identifier `name`.
+fn split_declarator(decl: &str) -> (String, String) {
+    let decl = decl.trim();
+    let (decl, array) = match decl.find('[') {
+        Some(i) => (&decl[..i], &decl[i..]),
+        None => (decl, ""),
     };
+    let decl = decl.trim_end();
+    let start = decl.rfind(|c: char| !(c.is_alphanumeric() || c == '_')).map_or(0, |i| i + 1);
+    (format!("{}{}", decl[..start].trim_end(), array), decl[start..].to_string())
+}
 
-    Cow::Borrowed(ty)
+/// Parses the comma-separated parameters of a function pointer type, e.g.
+///  `GLenum source,const void *userParam`. `void` alone stands for no parameters.
+fn parse_params(params: &str, tags: &mut BTreeSet<String>) -> Vec<Binding> {
+    if params.trim() == "void" {
+        return Vec::new();
+    }
+    params
+        .split(',')
+        .filter(|param| !param.trim().is_empty())
+        .map(|param| {
+            let (ty, ident) = split_declarator(param);
+            field(&ty, ident, tags)
+        })
+        .collect()
+}
+
+/// Parses the fields of a struct or union body, e.g. `int x, y; Display *display;`, dropping
+///  comments.
+fn parse_fields(body: &str, tags: &mut BTreeSet<String>) -> Vec<Binding> {
+    let mut body = body.to_string();
+    while let Some(start) = body.find("/*") {
+        let end = body[start..].find("*/").map_or(body.len(), |end| start + end + 2);
+        body.replace_range(start..end, " ");
+    }
+
+    let mut fields = Vec::new();
+    for decl in body.split(';').map(str::trim).filter(|decl| !decl.is_empty()) {
+        let mut declarators = decl.split(',');
+        let (ty, ident) = split_declarator(declarators.next().unwrap());
+        // the following declarators of `int x, y;` share the type without the pointers
+        let base = split_array(&ty).0.trim_end_matches(&['*', ' '][..]).to_string();
+        fields.push(field(&ty, ident, tags));
+        for declarator in declarators {
+            let (ptrs, ident) = split_declarator(declarator);
+            fields.push(field(&format!("{} {}", base, ptrs), ident, tags));
+        }
+    }
+    fields
+}
+
+fn field(ty: &str, ident: String, tags: &mut BTreeSet<String>) -> Binding {
+    tags.extend(struct_tag(ty));
+    Binding {
+        ident: underscore_keyword(ident),
+        ty: Cow::Owned(field_ty(ty)),
+        group: None,
+        len: None,
+    }
+}
+
+/// Converts a C preprocessor condition on the platform to a Rust `cfg` predicate.
+fn macro_cfg(name: &str) -> Option<&'static str> {
+    match name {
+        "__APPLE__" => Some("target_os = \"macos\""),
+        "_WIN32" => Some("windows"),
+        _ => None,
+    }
+}
+
+/// Parses the typedefs of a `<type>` consisting of preprocessor conditions, like
+///
+/// ```c
+/// #ifdef __APPLE__
+/// typedef void *GLhandleARB;
+/// #else
+/// typedef unsigned int GLhandleARB;
+/// #endif
+/// ```
+///
+/// Blocks with conditions other than the platform are skipped.
+fn parse_conditional_typedefs(ident: &str, src: &str, tags: &mut BTreeSet<String>) -> Vec<TypeDef> {
+    let mut defs = Vec::new();
+    let mut cfg = None;
+    for line in src.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("#ifdef ") {
+            cfg = macro_cfg(name.trim()).map(str::to_string);
+        } else if line.starts_with("#else") {
+            cfg = cfg.map(|cfg| format!("not({})", cfg));
+        } else if line.starts_with("#endif") {
+            cfg = None;
+        } else if let (Some(decl), Some(_)) = (line.strip_prefix("typedef ").and_then(|decl| decl.strip_suffix(';')), &cfg) {
+            let (ty, name) = split_declarator(decl);
+            if name == ident {
+                tags.extend(struct_tag(&ty));
+                defs.push(TypeDef {
+                    ident: name,
+                    cfg: cfg.clone(),
+                    kind: TypeKind::Alias(pointer_ty(&ty)),
+                });
+            }
+        }
+    }
+    defs
 }
 
 #[cfg(test)]
@@ -1285,6 +1363,68 @@ mod tests {
         }
     }
 
+    mod to_rust_ty {
+        use registry::parse;
+
+        #[test]
+        fn test_primitives() {
+            assert_eq!(parse::to_rust_ty("void"), "()");
+            assert_eq!(parse::to_rust_ty("unsigned int"), "__gl_imports::raw::c_uint");
+            assert_eq!(parse::to_rust_ty("khronos_int64_t"), "i64");
+            assert_eq!(parse::to_rust_ty("GLenum"), "types::GLenum");
+        }
+
+        #[test]
+        fn test_pointers() {
+            assert_eq!(parse::to_rust_ty("const void *"), "*const __gl_imports::raw::c_void");
+            assert_eq!(parse::to_rust_ty("const void **"), "*mut *const __gl_imports::raw::c_void");
+            assert_eq!(parse::to_rust_ty("const GLchar *const*"), "*const *const types::GLchar");
+            assert_eq!(parse::to_rust_ty("struct _cl_context *"), "*mut types::_cl_context");
+        }
+
+        #[test]
+        fn test_arrays() {
+            assert_eq!(parse::to_rust_ty("GLuint [2]"), "*mut [types::GLuint; 2]");
+            assert_eq!(parse::to_rust_ty("const GLfloat [4]"), "*const [types::GLfloat; 4]");
+        }
+    }
+
+    mod consume_types {
+        use registry::parse;
+        use {Api, Fallbacks, Profile, RegistryError};
+
+        fn parse(types: &str) -> Result<(), RegistryError> {
+            let filter = parse::Filter {
+                api: Api::Gl,
+                fallbacks: Fallbacks::All,
+                extensions: Default::default(),
+                profile: Profile::Core,
+                version: "1.0".to_string(),
+            };
+            let xml = format!("<registry><types>{}</types></registry>", types);
+            parse::from_xml(xml.as_bytes(), &filter, false).map(|_| ())
+        }
+
+        #[test]
+        fn test_unexpected_elements() {
+            match parse("<type>typedef int <name>GLint</name>;</type><enum/>") {
+                Err(RegistryError::UnexpectedXml { expected, found }) => {
+                    assert_eq!(expected, "</types>");
+                    assert!(found.contains("enum"));
+                },
+                result => panic!("Expected an error, found: {:?}", result),
+            }
+            match parse("<type>typedef int <foo/></type>") {
+                Err(RegistryError::UnexpectedXml { expected, .. }) => assert_eq!(expected, "</type>"),
+                result => panic!("Expected an error, found: {:?}", result),
+            }
+            match parse("<type>#include &lt;KHR/khrplatform.h&gt;</type>") {
+                Err(RegistryError::UnexpectedXml { expected, .. }) => assert_eq!(expected, "<name> in <type>"),
+                result => panic!("Expected an error, found: {:?}", result),
+            }
+        }
+    }
+
     mod parse_event {
         mod from_xml {
             use xml::{
@@ -0,0 +1,138 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+use std::{collections::BTreeSet, fs, io, path::Path};
+
+/// Returns the identifiers used in paths of the crate `krate` in the Rust sources at `path`, a file
+///  or a directory which is searched recursively, e.g. `Clear` and `COLOR_BUFFER_BIT` for
+///  `gl::Clear(gl::COLOR_BUFFER_BIT)` or `use gl::{Clear, COLOR_BUFFER_BIT};`.
+///
+/// Every segment of a path is returned, so modules like `types` are too. Pass the result to
+///  `RegistryBuilder::only`, which ignores the identifiers that aren't commands or enums.
+///
+/// Glob imports like `use gl::*;` hide the identifiers they import and are an `InvalidData` error,
+///  apart from those of `types`.
+pub fn used_idents<P: AsRef<Path>>(path: P, krate: &str) -> io::Result<BTreeSet<String>> {
+    let mut idents = BTreeSet::new();
+    scan_path(path.as_ref(), krate, &mut idents)?;
+    Ok(idents)
+}
+
+/// Reads an allowlist of identifiers for `RegistryBuilder::only`, one per line. Empty lines and
+///  everything after a `#` are ignored.
+pub fn read_allowlist<P: AsRef<Path>>(path: P) -> io::Result<BTreeSet<String>> {
+    let src = fs::read_to_string(path)?;
+    Ok(src
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|ident| !ident.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn scan_path(path: &Path, krate: &str, idents: &mut BTreeSet<String>) -> io::Result<()> {
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+            if path.is_dir() || path.extension().is_some_and(|ext| ext == "rs") {
+                scan_path(&path, krate, idents)?;
+            }
+        }
+        Ok(())
+    } else {
+        scan(&fs::read_to_string(path)?, krate, idents).map_err(|glob| {
+            let msg = format!("{}: the glob import `{}` hides the used identifiers, import them by name", path.display(), glob);
+            io::Error::new(io::ErrorKind::InvalidData, msg)
+        })
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Adds the identifiers following every `krate::` in `src`, including those in `{}` use trees.
+///
+/// Returns the path of the first glob import other than of `types` as the error.
+fn scan(src: &str, krate: &str, idents: &mut BTreeSet<String>) -> Result<(), String> {
+    let prefix = format!("{}::", krate);
+    for (start, _) in src.match_indices(&prefix) {
+        // e.g. `egl::` for the crate `gl`
+        if src[..start].chars().next_back().is_some_and(is_ident_char) {
+            continue;
+        }
+
+        let rest = &src[start + prefix.len()..];
+        let end = match rest.starts_with('{') {
+            true => {
+                let mut depth = 0;
+                rest.char_indices()
+                    .find(|&(_, c)| {
+                        match c {
+                            '{' => depth += 1,
+                            '}' => depth -= 1,
+                            _ => (),
+                        }
+                        depth == 0
+                    })
+                    .map_or(rest.len(), |(i, _)| i + 1)
+            },
+            false => rest.find(|c: char| !is_ident_char(c) && c != ':').unwrap_or(rest.len()),
+        };
+        // a `*` following the path, which is a glob rather than a multiplication after `::`
+        let end = match rest[end..].starts_with('*') && (end == 0 || rest[..end].ends_with("::")) {
+            true => end + 1,
+            false => end,
+        };
+
+        let path = &rest[..end];
+        let glob = path.match_indices('*').any(|(i, _)| {
+            let before = path[..i].trim_end();
+            (before.is_empty() || before.ends_with(['{', ',']) || before.ends_with("::")) && !before.ends_with("types::")
+        });
+        if glob {
+            return Err(format!("{}{}", prefix, path));
+        }
+
+        idents.extend(path.split(|c: char| !is_ident_char(c)).filter(|ident| ident.starts_with(|c: char| c.is_alphabetic() || c == '_')).map(str::to_string));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::scan;
+
+    #[test]
+    fn test_scan() {
+        let src = "
+use gl::{types::GLuint, BindBuffer, GenBuffers};
+unsafe {
+    gl::Clear(gl::COLOR_BUFFER_BIT);
+    gl::arrays::Uniform3f(location, [0.0; 3]);
+    egl::SwapBuffers(display, surface);
+}";
+        let mut idents = BTreeSet::new();
+        scan(src, "gl", &mut idents).unwrap();
+        assert_eq!(
+            idents.iter().map(|ident| &**ident).collect::<Vec<_>>(),
+            ["BindBuffer", "COLOR_BUFFER_BIT", "Clear", "GLuint", "GenBuffers", "Uniform3f", "arrays", "types"]
+        );
+    }
+
+    #[test]
+    fn test_scan_globs() {
+        let scan = |src| scan(src, "gl", &mut BTreeSet::new());
+        assert_eq!(scan("use gl::*;"), Err("gl::*".to_string()));
+        assert_eq!(scan("use gl::{arrays::*, Clear};"), Err("gl::{arrays::*, Clear}".to_string()));
+        assert_eq!(scan("use gl::{Clear, *};"), Err("gl::{Clear, *}".to_string()));
+        // the types are always kept
+        assert_eq!(scan("use gl::types::*;\nuse gl::{types::*, Clear};"), Ok(()));
+        assert_eq!(scan("let size = gl::FLOAT_SIZE*4 + gl::FLOAT_SIZE * 2;"), Ok(()));
+    }
+}
@@ -0,0 +1,45 @@
+////////////////////////////////////////////////////////////////////////////////////
+// Copyright (c) 2020 DasEtwas - All Rights Reserved                               /
+//      Unauthorized copying of this file, via any medium is strictly prohibited   /
+//      Proprietary and confidential                                               /
+////////////////////////////////////////////////////////////////////////////////////
+
+use gl_generator::{Api, CallStats, DebugPrints, GeneratorOptions, RegistryBuilder};
+use std::{env, fs::File, path::Path};
+
+fn main() {
+    // the parsed registry is cached in OUT_DIR, or in GL_GENERATOR_CACHE_DIR if set
+    println!("cargo:rerun-if-env-changed=GL_GENERATOR_CACHE_DIR");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let bindingsdest = Path::new(&out_dir).join("bindings.rs");
+
+    let mut file = File::create(&bindingsdest).expect("Could not create bindings file");
+
+    #[cfg(feature = "fn_calls_print")]
+    let print = DebugPrints::FunctionCalls;
+    #[cfg(not(feature = "fn_calls_print"))]
+    let print = DebugPrints::None;
+
+    #[cfg(feature = "call_stats")]
+    let call_stats = CallStats::Timing;
+    #[cfg(not(feature = "call_stats"))]
+    let call_stats = CallStats::None;
+
+    // GLES has no profiles
+    RegistryBuilder::new(Api::Gles2, (3, 2))
+        .extensions(&["GL_EXT_texture_filter_anisotropic"])
+        .options(GeneratorOptions {
+            debug_prints: print,
+            object_handles: true,
+            call_stats,
+            command_buffer: cfg!(feature = "command_buffer"),
+            shadow_state: cfg!(feature = "shadow_state"),
+            track_objects: cfg!(feature = "track_objects"),
+            ..Default::default()
+        })
+        .build()
+        .unwrap_or_else(|err| panic!("{}", err))
+        .write_bindings(gl_generator::GlobalTypedGenerator, &mut file)
+        .unwrap();
+}
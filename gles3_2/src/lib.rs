@@ -0,0 +1,5 @@
+//! OpenGL ES 3.2 bindings, generated into `OUT_DIR` by the build script.
+
+#![allow(clippy::all)]
+
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
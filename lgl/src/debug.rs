@@ -28,7 +28,7 @@ pub unsafe fn enable_gl_debug(log_level: GLErrorSeverityLogLevel) {
     // this is never freed, it leaks memory (1 byte)
     let log_level = Box::leak(Box::new(log_level));
 
-    gl::DebugMessageCallback(callback, log_level as *mut _ as *mut _);
+    gl::DebugMessageCallback(callback, log_level as *mut _ as *const _);
 
     assert!(GLErrorSeverityLogLevel::All < GLErrorSeverityLogLevel::DEBUG_SEVERITY_HIGH);
 
@@ -47,11 +47,11 @@ extern "system" fn callback(
     severity: GLenum,
     _length: GLsizei,
     message: *const GLchar,
-    log_level: *mut core::ffi::c_void,
+    log_level: *const core::ffi::c_void,
 ) {
     // return if the severity is not high enough
     if GLErrorSeverityLogLevel::from_gl(severity)
-        < unsafe { *(log_level as *mut GLErrorSeverityLogLevel) }
+        < unsafe { *(log_level as *const GLErrorSeverityLogLevel) }
     {
         return;
     }
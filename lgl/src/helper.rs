@@ -1,14 +1,11 @@
-pub fn setup_shader(shader: &str, s_type: gl::types::GLenum) -> u32 {
-    let shader_vec = vec![shader];
-    let size: Vec<i32> = shader_vec.iter().map(|y| y.len() as i32).collect();
-
+pub fn setup_shader(shader: &str, s_type: gl::types::GLenum) -> gl::Shader {
     let id = gl::CreateShader(s_type);
-    gl::ShaderSource(id, 1, shader_vec, &size[..]);
+    gl::slices::ShaderSource(id, vec![shader]);
     gl::CompileShader(id);
     id
 }
 
-pub fn apply_shaders(shader: Vec<u32>) -> u32 {
+pub fn apply_shaders(shader: Vec<gl::Shader>) -> gl::Program {
     let program = gl::CreateProgram();
     shader.iter().for_each(|&s| gl::AttachShader(program, s));
     gl::LinkProgram(program);
@@ -1,9 +1,17 @@
+#[cfg(all(feature = "desktop", feature = "gles"))]
+compile_error!("the `desktop` and `gles` features are mutually exclusive, use `--no-default-features --features gles`");
+#[cfg(not(any(feature = "desktop", feature = "gles")))]
+compile_error!("either the `desktop` or the `gles` feature has to be enabled");
+
+#[cfg(feature = "gles")]
+extern crate gles as gl;
+
 use std::mem::size_of;
 
 use gl::{
-    self, BindBuffer, BindTexture, BindVertexArray, BufferData, DrawArrays,
-    EnableVertexAttribArray, GenBuffers, GenTextures, GenVertexArrays, GenerateMipmap, PixelStorei,
-    TexImage2D, TexParameteri, VertexAttribPointer,
+    BindBuffer, BindTexture, BindVertexArray, DrawArrays, EnableVertexAttribArray, GenBuffers,
+    GenTextures, GenVertexArrays, GenerateMipmap, PixelStorei, TexImage2D, TexParameteri,
+    VertexAttribPointer,
 };
 use glutin::event::{Event, WindowEvent};
 
@@ -20,13 +28,25 @@ static VERTICES: &[f32] = &[
     -0.5,  0.5, 0.0,   1.0, 1.0, 0.0,   0.0, 1.0    // top left 
 ];
 
+#[cfg(feature = "desktop")]
 const VS_SRC: &str = include_str!("shader/test.vert");
+#[cfg(feature = "desktop")]
 const FS_SRC: &str = include_str!("shader/test.frag");
+#[cfg(feature = "gles")]
+const VS_SRC: &str = include_str!("shader/test_es.vert");
+#[cfg(feature = "gles")]
+const FS_SRC: &str = include_str!("shader/test_es.frag");
+
+#[cfg(feature = "desktop")]
+const GL_REQUEST: glutin::GlRequest = glutin::GlRequest::Latest;
+#[cfg(feature = "gles")]
+const GL_REQUEST: glutin::GlRequest = glutin::GlRequest::Specific(glutin::Api::OpenGlEs, (3, 2));
 
 fn main() {
     let el = glutin::event_loop::EventLoop::new();
     let wb = glutin::window::WindowBuilder::new().with_title("Men");
     let w_context = glutin::ContextBuilder::new()
+        .with_gl(GL_REQUEST)
         .build_windowed(wb, &el)
         .unwrap();
     let w_context = unsafe { w_context.make_current().unwrap() };
@@ -45,7 +65,7 @@ fn main() {
     let image_data = image_buffer.as_raw();
     let image_data_len = image_data.len();
 
-    let texture = GenTextures(1);
+    let texture = GenTextures();
 
     BindTexture(gl::TEXTURE_2D, texture);
     // set the texture wrapping/filtering options (on the currently bound texture object)
@@ -54,20 +74,26 @@ fn main() {
     TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as _);
     TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as _);
 
-    TexImage2D(
-        gl::TEXTURE_2D,
-        0,
-        gl::RGB as _,
-        image_buffer.width() as _,
-        image_buffer.height() as _,
-        0,
-        gl::RGB,
-        gl::UNSIGNED_BYTE, // 0..=255
-        image_data.as_ptr() as *const _,
-    );
+    // the image holds width * height RGB8 pixels, with 1 byte alignment
+    unsafe {
+        TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::RGB as _,
+            image_buffer.width() as _,
+            image_buffer.height() as _,
+            0,
+            gl::RGB,
+            gl::UNSIGNED_BYTE, // 0..=255
+            image_data.as_ptr() as *const _,
+        )
+    };
     GenerateMipmap(gl::TEXTURE_2D);
 
     let program = helper::apply_shaders(vec![fs, vs]);
+    // the program keeps the compiled shaders
+    gl::DeleteShader(vs);
+    gl::DeleteShader(fs);
 
     // bind texture to ouruniform
     gl::UseProgram(program);
@@ -76,17 +102,12 @@ fn main() {
     let texture_uniform_location = gl::GetUniformLocation(program, "ourTexture");
     gl::Uniform1i(texture_uniform_location, texture_slot);
 
-    let va = GenVertexArrays(1);
+    let va = GenVertexArrays();
     BindVertexArray(va);
 
-    let vb = GenBuffers(1);
+    let vb = GenBuffers();
     BindBuffer(gl::ARRAY_BUFFER, vb);
-    BufferData(
-        gl::ARRAY_BUFFER,
-        (VERTICES.len() * size_of::<f32>()) as isize,
-        VERTICES.as_ptr() as *const _,
-        gl::STATIC_DRAW,
-    );
+    gl::slices::BufferData(gl::ARRAY_BUFFER, VERTICES, gl::STATIC_DRAW);
 
     let position_location = 0;
     let position_offset = 0;
@@ -94,46 +115,60 @@ fn main() {
     let colors_offset = 3 * size_of::<f32>();
     let uv_location = 2;
     let uv_offset = colors_offset + 3 * size_of::<f32>();
-    // PPPCCCTT...
+    // PPPCCCTT..., the offsets are into the bound array buffer
     let stride = (3 + 3 + 2) * size_of::<f32>();
     // location 0 (vertex position)
-    VertexAttribPointer(
-        position_location,
-        3,
-        gl::FLOAT,
-        0,
-        stride as i32,
-        position_offset as usize as *const _,
-    );
+    unsafe {
+        VertexAttribPointer(
+            position_location,
+            3,
+            gl::FLOAT,
+            0,
+            stride as i32,
+            position_offset as usize as *const _,
+        )
+    };
     EnableVertexAttribArray(position_location);
 
     // location 1 (texture coordinate)
-    VertexAttribPointer(
-        colors_location,
-        3,
-        gl::FLOAT,
-        0,
-        stride as i32,
-        colors_offset as usize as *const _,
-    );
+    unsafe {
+        VertexAttribPointer(
+            colors_location,
+            3,
+            gl::FLOAT,
+            0,
+            stride as i32,
+            colors_offset as usize as *const _,
+        )
+    };
     EnableVertexAttribArray(colors_location);
 
     // location 2 (texture coordinate)
-    VertexAttribPointer(
-        uv_location,
-        2,
-        gl::FLOAT,
-        0,
-        stride as i32,
-        uv_offset as usize as *const _,
-    );
+    unsafe {
+        VertexAttribPointer(
+            uv_location,
+            2,
+            gl::FLOAT,
+            0,
+            stride as i32,
+            uv_offset as usize as *const _,
+        )
+    };
     EnableVertexAttribArray(uv_location);
 
     el.run(move |event, _, control_flow| {
         *control_flow = glutin::event_loop::ControlFlow::Poll;
 
         match event {
-            Event::LoopDestroyed => return,
+            Event::LoopDestroyed => {
+                gl::DeleteProgram(program);
+                gl::slices::DeleteBuffers(&[vb]);
+                gl::slices::DeleteVertexArrays(&[va]);
+                gl::slices::DeleteTextures(&[texture]);
+
+                #[cfg(feature = "track_objects")]
+                gl::objects::report();
+            }
             Event::WindowEvent { event, .. } => match event {
                 WindowEvent::Resized(new_size) => w_context.resize(new_size),
                 WindowEvent::CloseRequested => {